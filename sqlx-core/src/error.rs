@@ -87,6 +87,16 @@ pub enum Error {
     /// [`Pool::close`]: crate::pool::Pool::close
     #[error("attempted to acquire a connection on a closed pool")]
     PoolClosed,
+
+    /// The background thread of a connection, such as the one that runs all calls into
+    /// SQLite, has stopped unexpectedly.
+    #[error("attempted to communicate with a crashed background worker")]
+    WorkerCrashed,
+
+    /// The transaction was rolled back by the database, such as after an error inside of it,
+    /// before it could be committed.
+    #[error("attempted to commit a transaction that was already rolled back by the database")]
+    TransactionRolledBack,
 }
 
 impl Error {
@@ -3,6 +3,7 @@ use crate::encode::Encode;
 use crate::mssql::database::Mssql;
 use crate::mssql::io::MssqlBufMutExt;
 use crate::mssql::protocol::rpc::StatusFlags;
+use crate::mssql::protocol::type_info::{DataType, MAX_LEN};
 use crate::types::Type;
 
 #[derive(Default)]
@@ -42,7 +43,16 @@ impl MssqlArguments {
         name: &str,
         initial_value: T,
     ) {
-        let ty = initial_value.produces().unwrap_or_else(T::type_info);
+        let mut ty = initial_value.produces().unwrap_or_else(T::type_info);
+
+        // the value the server leaves in an output parameter is likely to be longer than
+        // the initial value we pass in
+        if matches!(
+            ty.0.ty,
+            DataType::NVarChar | DataType::BigVarChar | DataType::BigVarBinary
+        ) {
+            ty.0.size = MAX_LEN;
+        }
 
         let mut ty_name = String::new();
         ty.0.fmt(&mut ty_name);
@@ -0,0 +1,182 @@
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::from_utf8;
+use std::time::Duration;
+
+use sqlx_rt::{timeout, UdpSocket};
+
+use crate::error::Error;
+
+// the SQL Server Browser service listens for requests on UDP port 1434
+const BROWSER_PORT: u16 = 1434;
+
+// the browser does not answer at all for an instance it does not know
+const BROWSER_TIMEOUT: Duration = Duration::from_secs(5);
+
+// CLNT_UCAST_INST: asks for the details of a single instance
+const CLNT_UCAST_INST: u8 = 0x04;
+
+// SVR_RESP
+const SVR_RESP: u8 = 0x05;
+
+// asks the SQL Server Browser on the host for the TCP port of a named instance
+// https://docs.microsoft.com/en-us/openspecs/windows_protocols/mc-sqlr
+pub(super) async fn resolve_port(host: &str, instance: &str) -> Result<u16, Error> {
+    resolve_port_with(host, BROWSER_PORT, instance).await
+}
+
+async fn resolve_port_with(host: &str, port: u16, instance: &str) -> Result<u16, Error> {
+    let addr = lookup_host(host, port).await?;
+
+    // the local socket must be of the same address family as the browser
+    let local: SocketAddr = match addr {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+
+    // NOTE: tokio needs a mutable socket to send and receive
+    #[allow(unused_mut)]
+    let mut socket = UdpSocket::bind(local).await?;
+
+    socket.connect(addr).await?;
+
+    let mut request = Vec::with_capacity(instance.len() + 2);
+    request.push(CLNT_UCAST_INST);
+    request.extend_from_slice(instance.as_bytes());
+    request.push(0);
+
+    socket.send(&request).await?;
+
+    let mut buf = vec![0; 4096];
+
+    let len = timeout(BROWSER_TIMEOUT, socket.recv(&mut buf))
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "SQL Server Browser on {} did not answer for instance {:?}",
+                    host, instance
+                ),
+            )
+        })??;
+
+    parse_response(&buf[..len], instance)
+}
+
+async fn lookup_host(host: &str, port: u16) -> io::Result<SocketAddr> {
+    #[cfg(feature = "runtime-async-std")]
+    let mut addrs = sqlx_rt::async_std::net::ToSocketAddrs::to_socket_addrs(&(host, port)).await?;
+
+    #[cfg(any(feature = "runtime-actix", feature = "runtime-tokio"))]
+    let mut addrs = sqlx_rt::tokio::net::lookup_host((host, port)).await?;
+
+    addrs.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no addresses found for {}", host),
+        )
+    })
+}
+
+// SVR_RESP is a list of instances, each a list of `;`-separated keys and values that ends
+// with an empty value:
+//  ServerName;HOST;InstanceName;SQLEXPRESS;IsClustered;No;Version;15.0.2000.5;tcp;49703;;
+fn parse_response(buf: &[u8], instance: &str) -> Result<u16, Error> {
+    if buf.len() < 3 || buf[0] != SVR_RESP {
+        return Err(err_protocol!("SQL Server Browser: unexpected response"));
+    }
+
+    let len = u16::from_le_bytes([buf[1], buf[2]]) as usize;
+    let data = buf
+        .get(3..(3 + len))
+        .ok_or_else(|| err_protocol!("SQL Server Browser: truncated response"))?;
+
+    let data = from_utf8(data).map_err(Error::protocol)?;
+
+    for info in data.split(";;") {
+        let mut parts = info.split(';');
+        let mut name = None;
+        let mut port = None;
+
+        while let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            if key.eq_ignore_ascii_case("InstanceName") {
+                name = Some(value);
+            } else if key.eq_ignore_ascii_case("tcp") {
+                port = Some(value);
+            }
+        }
+
+        match name {
+            Some(name) if name.eq_ignore_ascii_case(instance) => {}
+            _ => continue,
+        }
+
+        return match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| err_protocol!("SQL Server Browser: invalid port {:?}", port)),
+
+            None => Err(err_protocol!(
+                "SQL Server Browser: instance {:?} does not accept TCP connections",
+                instance
+            )),
+        };
+    }
+
+    Err(err_protocol!(
+        "SQL Server Browser: unknown instance {:?}",
+        instance
+    ))
+}
+
+#[test]
+fn test_parse_response() {
+    let data =
+        b"ServerName;HOST;InstanceName;SQLEXPRESS;IsClustered;No;Version;15.0.2000.5;tcp;49703;;";
+
+    let mut buf = vec![SVR_RESP];
+    buf.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    buf.extend_from_slice(data);
+
+    assert_eq!(parse_response(&buf, "sqlexpress").unwrap(), 49703);
+    assert!(parse_response(&buf, "OTHER").is_err());
+}
+
+#[cfg(feature = "runtime-async-std")]
+#[test]
+fn test_resolve_port() {
+    sqlx_rt::async_std::task::block_on(async {
+        resolve_port_on("127.0.0.1").await;
+
+        // not every machine has an IPv6 loopback
+        if std::net::UdpSocket::bind(("::1", 0)).is_ok() {
+            resolve_port_on("::1").await;
+        }
+    });
+}
+
+#[cfg(all(test, feature = "runtime-async-std"))]
+async fn resolve_port_on(host: &str) {
+    // a stand-in for the browser service that knows of a single instance
+    let responder = UdpSocket::bind((host, 0)).await.unwrap();
+    let port = responder.local_addr().unwrap().port();
+
+    sqlx_rt::spawn(async move {
+        let mut buf = [0; 64];
+        let (len, peer) = responder.recv_from(&mut buf).await.unwrap();
+
+        assert_eq!(&buf[..len], b"\x04TEST\0");
+
+        let data = b"ServerName;HOST;InstanceName;TEST;IsClustered;No;tcp;50001;;";
+        let mut response = vec![SVR_RESP];
+        response.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        response.extend_from_slice(data);
+
+        responder.send_to(&response, peer).await.unwrap();
+    });
+
+    let resolved = resolve_port_with(host, port, "TEST").await.unwrap();
+
+    assert_eq!(resolved, 50001);
+}
@@ -1,11 +1,13 @@
 use crate::error::Error;
 use crate::io::Decode;
 use crate::mssql::connection::stream::MssqlStream;
+use crate::mssql::connection::tls;
+use crate::mssql::protocol::feature_ext::{FeatureAck, FeatureId};
 use crate::mssql::protocol::login::Login7;
 use crate::mssql::protocol::message::Message;
 use crate::mssql::protocol::packet::PacketType;
 use crate::mssql::protocol::pre_login::{Encrypt, PreLogin, Version};
-use crate::mssql::{MssqlConnectOptions, MssqlConnection};
+use crate::mssql::{MssqlConnectOptions, MssqlConnection, MssqlEncrypt};
 
 impl MssqlConnection {
     pub(crate) async fn establish(options: &MssqlConnectOptions) -> Result<Self, Error> {
@@ -14,14 +16,18 @@ impl MssqlConnection {
         // Send PRELOGIN to set up the context for login. The server should immediately
         // respond with a PRELOGIN message of its own.
 
-        // TODO: Encryption
         // TODO: Send the version of SQLx over
 
+        let encryption = match options.encrypt {
+            MssqlEncrypt::Disabled => Encrypt::NOT_SUPPORTED,
+            MssqlEncrypt::Preferred | MssqlEncrypt::Required => Encrypt::ON,
+        };
+
         stream.write_packet(
             PacketType::PreLogin,
             PreLogin {
                 version: Version::default(),
-                encryption: Encrypt::NOT_SUPPORTED,
+                encryption,
 
                 ..Default::default()
             },
@@ -29,8 +35,13 @@ impl MssqlConnection {
 
         stream.flush().await?;
 
-        let (_, packet) = stream.recv_packet().await?;
-        let _ = PreLogin::decode(packet)?;
+        let (_, packet) = stream.recv_response().await?;
+        let pre_login = PreLogin::decode(packet)?;
+
+        // the server answers with the encryption it settled on; when TLS is used, it is set up
+        // here so that LOGIN7, and everything after it, is encrypted
+
+        tls::maybe_upgrade(&mut stream, options, pre_login.encryption).await?;
 
         // LOGIN7 defines the authentication rules for use between client and server
 
@@ -39,23 +50,29 @@ impl MssqlConnection {
             Login7 {
                 // FIXME: use a version constant
                 version: 0x74000004, // SQL Server 2012 - SQL Server 2019
-                client_program_version: 0,
+                client_program_version: options.client_version,
                 client_pid: 0,
-                packet_size: 4096,
-                hostname: "",
+                packet_size: options.packet_size,
+                hostname: &options.workstation,
                 username: &options.username,
                 password: options.password.as_deref().unwrap_or_default(),
-                app_name: "",
-                server_name: "",
+                app_name: &options.app_name,
+                server_name: &options.host,
                 client_interface_name: "",
-                language: "",
+                language: &options.language,
                 database: &*options.database,
                 client_id: [0; 6],
+
+                // ask the server to send VARCHAR data in UTF-8 collations as is
+                features: &[FeatureId::Utf8Support],
             },
         );
 
         stream.flush().await?;
 
+        // the server answers with an ENVCHANGE for the packet size it settled on, which may
+        // differ from the one we asked for
+
         loop {
             // NOTE: we should receive an [Error] message if something goes wrong, otherwise,
             //       all messages are mostly informational (ENVCHANGE, INFO, LOGINACK)
@@ -66,6 +83,25 @@ impl MssqlConnection {
                     // no action is needed, we are just going to keep waiting till we hit <Done>
                 }
 
+                Message::FeatureExtAck(ack) => {
+                    for feature in ack.features {
+                        match feature {
+                            // servers before SQL Server 2019 do not acknowledge UTF8_SUPPORT;
+                            // either way, the collation of each column tells whether its
+                            // data is UTF-8
+                            FeatureAck::Utf8Support { .. } => {}
+
+                            // the server must only acknowledge the features we asked for
+                            feature => {
+                                return Err(err_protocol!(
+                                    "LOGIN7: unexpected acknowledgement of {:?}",
+                                    feature
+                                ));
+                            }
+                        }
+                    }
+                }
+
                 Message::Done(_) => {
                     break;
                 }
@@ -2,19 +2,18 @@ use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::TryStreamExt;
-use once_cell::sync::Lazy;
-use regex::Regex;
 
 use crate::describe::{Column, Describe};
 use crate::error::Error;
 use crate::executor::{Execute, Executor};
-use crate::mssql::protocol::col_meta_data::Flags;
 use crate::mssql::protocol::done::Status;
 use crate::mssql::protocol::message::Message;
 use crate::mssql::protocol::packet::PacketType;
 use crate::mssql::protocol::rpc::{OptionFlags, Procedure, RpcRequest};
 use crate::mssql::protocol::sql_batch::SqlBatch;
+use crate::mssql::protocol::type_info::TypeInfo;
 use crate::mssql::{Mssql, MssqlArguments, MssqlConnection, MssqlRow, MssqlTypeInfo};
+use crate::row::Row;
 
 impl MssqlConnection {
     async fn run(&mut self, query: &str, arguments: Option<MssqlArguments>) -> Result<(), Error> {
@@ -146,91 +145,89 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
     {
         let s = query.query();
 
-        // [sp_prepare] will emit the column meta data
-        // small issue is that we need to declare all the used placeholders with a "fallback" type
-        // we currently use regex to collect them; false positives are *okay* but false
-        // negatives would break the query
-        let proc = Either::Right(Procedure::Prepare);
-
-        // NOTE: this does not support unicode identifiers; as we don't even support
-        //       named parameters (yet) this is probably fine, for now
+        Box::pin(async move {
+            // [sp_describe_undeclared_parameters] suggests a type for each of the parameters
+            // from how they are used in the query
+            let result = self
+                .call("sp_describe_undeclared_parameters")
+                .bind_named("@tsql", s)
+                .execute()
+                .await?;
+
+            let mut params = Vec::new();
+            let mut declarations = String::new();
+
+            for row in result.rows() {
+                let ordinal: i32 = row.try_get(0)?;
+                let name: String = row.try_get(1)?;
+                let type_name: String = row.try_get(3)?;
+
+                let type_info = TypeInfo::from_system_type(
+                    row.try_get(2)?,
+                    row.try_get(4)?,
+                    row.try_get::<i8, _>(5)? as u8,
+                    row.try_get::<i8, _>(6)? as u8,
+                );
+
+                // parameters are listed in the order they first appear in the query, which
+                // need not be the order of their names; @p1, @p2, ... @pN
+                let index = name
+                    .strip_prefix("@p")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .and_then(|n| n.checked_sub(1))
+                    .unwrap_or(ordinal as usize - 1);
+
+                if params.len() <= index {
+                    params.resize(index + 1, None);
+                }
 
-        static PARAMS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"@p[[:alnum:]]+").unwrap());
+                params[index] = type_info.map(MssqlTypeInfo);
 
-        let mut params = String::new();
-        let mut num_params = 0;
+                if !declarations.is_empty() {
+                    declarations.push(',');
+                }
 
-        for m in PARAMS_RE.captures_iter(s) {
-            if !params.is_empty() {
-                params.push_str(",");
+                declarations.push_str(&name);
+                declarations.push(' ');
+                declarations.push_str(&type_name);
             }
 
-            params.push_str(&m[0]);
-
-            // NOTE: this means that a query! of `SELECT @p1` will have the macros believe
-            //       it will return nvarchar(1); this is a greater issue with `query!` that we
-            //       we need to circle back to. This doesn't happen much in practice however.
-            params.push_str(" nvarchar(1)");
+            // [sp_describe_first_result_set] needs the parameters to be declared
+            let mut call = self
+                .call("sp_describe_first_result_set")
+                .bind_named("@tsql", s);
 
-            num_params += 1;
-        }
-
-        let params = if params.is_empty() {
-            None
-        } else {
-            Some(&*params)
-        };
-
-        let mut args = MssqlArguments::default();
-
-        args.declare("", 0_i32);
-        args.add_unnamed(params);
-        args.add_unnamed(s);
-        args.add_unnamed(0x0001_i32); // 1 = SEND_METADATA
-
-        self.stream.write_packet(
-            PacketType::Rpc,
-            RpcRequest {
-                transaction_descriptor: self.stream.transaction_descriptor,
-                arguments: &args,
-                procedure: proc,
-                options: OptionFlags::empty(),
-            },
-        );
-
-        Box::pin(async move {
-            self.stream.flush().await?;
-            self.stream.wait_until_ready().await?;
-            self.stream.pending_done_count += 1;
+            if !declarations.is_empty() {
+                call = call.bind_named("@params", &*declarations);
+            }
 
-            loop {
-                match self.stream.recv_message().await? {
-                    Message::DoneProc(done) | Message::Done(done) => {
-                        if !done.status.contains(Status::DONE_MORE) {
-                            // done with prepare
-                            self.stream.handle_done(&done);
-                            break;
-                        }
-                    }
+            let result = call.execute().await?;
+            let mut columns = Vec::new();
 
-                    _ => {}
+            for row in result.rows() {
+                // hidden columns are only returned for browse mode
+                if row.try_get::<bool, _>(0)? {
+                    continue;
                 }
-            }
 
-            let mut columns = Vec::with_capacity(self.stream.columns.len());
+                let name: Option<String> = row.try_get(2)?;
+                let nullable: bool = row.try_get(3)?;
+
+                let type_info = TypeInfo::from_system_type(
+                    row.try_get(4)?,
+                    row.try_get(6)?,
+                    row.try_get::<i8, _>(7)? as u8,
+                    row.try_get::<i8, _>(8)? as u8,
+                );
 
-            for col in &self.stream.columns {
                 columns.push(Column {
-                    name: col.col_name.clone(),
-                    type_info: Some(MssqlTypeInfo(col.type_info.clone())),
-                    not_null: Some(!col.flags.contains(Flags::NULLABLE)),
+                    name: name.unwrap_or_default(),
+                    type_info: type_info.map(MssqlTypeInfo),
+                    not_null: Some(!nullable),
                 });
             }
 
-            Ok(Describe {
-                params: vec![None; num_params],
-                columns,
-            })
+            Ok(Describe { params, columns })
         })
     }
 }
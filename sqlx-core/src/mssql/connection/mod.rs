@@ -10,9 +10,11 @@ use crate::executor::Executor;
 use crate::mssql::connection::stream::MssqlStream;
 use crate::mssql::{Mssql, MssqlConnectOptions};
 
+mod browser;
 mod establish;
 mod executor;
 mod stream;
+mod tls;
 
 pub struct MssqlConnection {
     pub(crate) stream: MssqlStream,
@@ -1,14 +1,18 @@
+use std::cmp;
 use std::ops::{Deref, DerefMut};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use sqlx_rt::TcpStream;
 
 use crate::error::Error;
 use crate::io::{BufStream, Encode};
+use crate::mssql::connection::browser;
+use crate::mssql::connection::tls::TlsPreloginWrapper;
 use crate::mssql::protocol::col_meta_data::{ColMetaData, ColumnData};
 use crate::mssql::protocol::done::{Done, Status as DoneStatus};
 use crate::mssql::protocol::env_change::EnvChange;
 use crate::mssql::protocol::error::Error as ProtocolError;
+use crate::mssql::protocol::feature_ext::FeatureExtAck;
 use crate::mssql::protocol::info::Info;
 use crate::mssql::protocol::login_ack::LoginAck;
 use crate::mssql::protocol::message::{Message, MessageType};
@@ -21,7 +25,7 @@ use crate::mssql::{MssqlConnectOptions, MssqlDatabaseError};
 use crate::net::MaybeTlsStream;
 
 pub(crate) struct MssqlStream {
-    inner: BufStream<MaybeTlsStream<TcpStream>>,
+    inner: BufStream<MaybeTlsStream<TlsPreloginWrapper<TcpStream>>>,
 
     // how many Done (or Error) we are currently waiting for
     pub(crate) pending_done_count: usize,
@@ -30,6 +34,14 @@ pub(crate) struct MssqlStream {
     // set from ENVCHANGE on `BEGIN` and reset to `0` on a ROLLBACK
     pub(crate) transaction_descriptor: u64,
 
+    // set when a transaction is begun with an isolation level, which stays in effect for the
+    // session until it is reset once that transaction ends
+    pub(crate) reset_isolation_level: bool,
+
+    // the largest packet, including its header, that may be sent to the server
+    // 4 KiB until the server tells us the packet size it settled on during login
+    packet_size: usize,
+
     // current TabularResult from the server that we are iterating over
     response: Option<(PacketHeader, Bytes)>,
 
@@ -40,9 +52,14 @@ pub(crate) struct MssqlStream {
 
 impl MssqlStream {
     pub(super) async fn connect(options: &MssqlConnectOptions) -> Result<Self, Error> {
-        let inner = BufStream::new(MaybeTlsStream::Raw(
-            TcpStream::connect((&*options.host, options.port)).await?,
-        ));
+        let port = match &options.instance {
+            Some(instance) => browser::resolve_port(&options.host, instance).await?,
+            None => options.port,
+        };
+
+        let inner = BufStream::new(MaybeTlsStream::Raw(TlsPreloginWrapper::new(
+            TcpStream::connect((&*options.host, port)).await?,
+        )));
 
         Ok(Self {
             inner,
@@ -50,43 +67,59 @@ impl MssqlStream {
             response: None,
             pending_done_count: 0,
             transaction_descriptor: 0,
+            reset_isolation_level: false,
+            packet_size: 4096,
         })
     }
 
-    // writes the packet out to the write buffer
-    // will (eventually) handle packet chunking
+    // writes the message out to the write buffer, split into as many packets as it takes
     pub(crate) fn write_packet<'en, T: Encode<'en>>(&mut self, ty: PacketType, payload: T) {
-        // TODO: Support packet chunking for large packet sizes
-        //       We likely need to double-buffer the writes so we know to chunk
+        // write out the payload first so we know how many packets it needs
+        let offset = self.inner.wbuf.len();
+        self.inner.write(payload);
 
-        // write out the packet header, leaving room for setting the packet length later
+        let payload = self.inner.wbuf.split_off(offset);
+        let max_len = self.packet_size - 8;
 
-        let mut len_offset = 0;
+        let mut start = 0;
+        let mut packet_id = 1_u8;
 
-        self.inner.write_with(
-            PacketHeader {
-                r#type: ty,
-                status: Status::END_OF_MESSAGE,
-                length: 0,
-                server_process_id: 0,
-                packet_id: 1,
-            },
-            &mut len_offset,
-        );
+        // NOTE: an empty message is still sent as a single, empty packet
+        loop {
+            let end = cmp::min(start + max_len, payload.len());
+            let last = end == payload.len();
+
+            self.inner.write_with(
+                PacketHeader {
+                    r#type: ty,
+                    status: if last {
+                        Status::END_OF_MESSAGE
+                    } else {
+                        Status::NORMAL
+                    },
+                    length: (end - start + 8) as u16,
+                    server_process_id: 0,
+                    packet_id,
+                },
+                &mut 0,
+            );
+
+            self.inner.wbuf.extend_from_slice(&payload[start..end]);
+
+            if last {
+                break;
+            }
 
-        // write out the payload
-        self.inner.write(payload);
+            start = end;
 
-        // overwrite the packet length now that we know it
-        let len = self.inner.wbuf.len();
-        self.inner.wbuf[len_offset..(len_offset + 2)].copy_from_slice(&(len as u16).to_be_bytes());
+            // the packet ID wraps around after 255 packets
+            packet_id = packet_id.wrapping_add(1);
+        }
     }
 
     // receive the next packet from the database
     // blocks until a packet is available
-    pub(super) async fn recv_packet(&mut self) -> Result<(PacketHeader, Bytes), Error> {
-        // TODO: Support packet chunking for large packet sizes
-
+    async fn recv_packet(&mut self) -> Result<(PacketHeader, Bytes), Error> {
         let header: PacketHeader = self.inner.read(8).await?;
 
         // NOTE: From what I can tell, the response type from the server should ~always~
@@ -98,77 +131,123 @@ impl MssqlStream {
             ));
         }
 
-        let payload_len = (header.length - 8) as usize;
+        let payload_len = (header.length as usize).checked_sub(8).ok_or_else(|| {
+            err_protocol!("received packet with invalid length {}", header.length)
+        })?;
+
         let payload: Bytes = self.inner.read(payload_len).await?;
 
         Ok((header, payload))
     }
 
+    // receive the next message from the database, reassembled from as many packets as
+    // it was split into
+    // NOTE: only used for PRELOGIN, whose response is not a token stream; token streams are
+    //       decoded packet by packet in `recv_message`
+    pub(super) async fn recv_response(&mut self) -> Result<(PacketHeader, Bytes), Error> {
+        let (header, payload) = self.recv_packet().await?;
+
+        if header.status.contains(Status::END_OF_MESSAGE) {
+            return Ok((header, payload));
+        }
+
+        let mut buf = BytesMut::from(&*payload);
+
+        loop {
+            let (header, payload) = self.recv_packet().await?;
+            buf.extend_from_slice(&payload);
+
+            if header.status.contains(Status::END_OF_MESSAGE) {
+                return Ok((header, buf.freeze()));
+            }
+        }
+    }
+
+    // receive the next packet of the current TabularResult and append it to what is left of
+    // the previous one; tokens are not aligned to packets and may continue in the next one
+    async fn recv_continuation(&mut self) -> Result<(), Error> {
+        let (header, payload) = self.recv_packet().await?;
+
+        self.response = Some(match self.response.take() {
+            Some((_, rest)) if !rest.is_empty() => {
+                let mut buf = BytesMut::with_capacity(rest.len() + payload.len());
+                buf.extend_from_slice(&rest);
+                buf.extend_from_slice(&payload);
+
+                (header, buf.freeze())
+            }
+
+            _ => (header, payload),
+        });
+
+        Ok(())
+    }
+
     // receive the next ~message~
     // TDS communicates in streams of packets that are themselves streams of messages
-    pub(super) async fn recv_message(&mut self) -> Result<Message, Error> {
+    pub(crate) async fn recv_message(&mut self) -> Result<Message, Error> {
         loop {
-            while self.response.as_ref().map_or(false, |r| !r.1.is_empty()) {
-                let buf = if let Some((_, buf)) = self.response.as_mut() {
-                    buf
-                } else {
-                    // this shouldn't be reachable but just nope out
-                    // and head to refill our buffer
-                    break;
-                };
-
-                let ty = MessageType::get(buf)?;
-
-                let message = match ty {
-                    MessageType::EnvChange => {
-                        match EnvChange::get(buf)? {
-                            EnvChange::BeginTransaction(desc) => {
-                                self.transaction_descriptor = desc;
-                            }
+            let (mut buf, more) = match &self.response {
+                Some((header, buf)) if !buf.is_empty() => {
+                    (buf.clone(), !header.status.contains(Status::END_OF_MESSAGE))
+                }
 
-                            EnvChange::CommitTransaction(_) | EnvChange::RollbackTransaction(_) => {
-                                self.transaction_descriptor = 0;
-                            }
+                // no message from the server to iterate (or its empty); fill our buffer
+                _ => {
+                    self.recv_continuation().await?;
+                    continue;
+                }
+            };
 
-                            _ => {}
-                        }
+            let token = match Token::get(&mut buf, &mut self.columns) {
+                Ok(token) => token,
 
-                        continue;
-                    }
+                // the token is cut short by the end of the packet; read in the next one
+                // and decode it again from the start
+                Err(_) if more => {
+                    self.recv_continuation().await?;
+                    continue;
+                }
+
+                Err(error) => return Err(error),
+            };
+
+            if let Some((_, response)) = &mut self.response {
+                *response = buf;
+            }
 
-                    MessageType::Info => {
-                        let _ = Info::get(buf)?;
-                        continue;
+            match token {
+                Token::Message(message) => return Ok(message),
+
+                Token::EnvChange(change) => match change {
+                    EnvChange::BeginTransaction(desc) => {
+                        self.transaction_descriptor = desc;
                     }
 
-                    MessageType::Row => Message::Row(Row::get(buf, false, &self.columns)?),
-                    MessageType::NbcRow => Message::Row(Row::get(buf, true, &self.columns)?),
-                    MessageType::LoginAck => Message::LoginAck(LoginAck::get(buf)?),
-                    MessageType::ReturnStatus => Message::ReturnStatus(ReturnStatus::get(buf)?),
-                    MessageType::ReturnValue => Message::ReturnValue(ReturnValue::get(buf)?),
-                    MessageType::Done => Message::Done(Done::get(buf)?),
-                    MessageType::DoneInProc => Message::DoneInProc(Done::get(buf)?),
-                    MessageType::DoneProc => Message::DoneProc(Done::get(buf)?),
-                    MessageType::Order => Message::Order(Order::get(buf)?),
-
-                    MessageType::Error => {
-                        let error = ProtocolError::get(buf)?;
-                        return self.handle_error(error);
+                    EnvChange::CommitTransaction(_) | EnvChange::RollbackTransaction(_) => {
+                        self.transaction_descriptor = 0;
                     }
 
-                    MessageType::ColMetaData => {
-                        // NOTE: there isn't anything to return as the data gets
-                        //       consumed by the stream for use in subsequent Row decoding
-                        ColMetaData::get(buf, &mut self.columns)?;
-                        continue;
+                    EnvChange::PacketSize(size) => {
+                        self.packet_size = match size.parse() {
+                            Ok(size) if (512..=32767).contains(&size) => size,
+
+                            _ => {
+                                return Err(err_protocol!(
+                                    "invalid packet size in ENVCHANGE: {:?}",
+                                    size
+                                ));
+                            }
+                        };
                     }
-                };
 
-                return Ok(message);
-            }
+                    _ => {}
+                },
 
-            // no packet from the server to iterate (or its empty); fill our buffer
-            self.response = Some(self.recv_packet().await?);
+                Token::Info => {}
+
+                Token::Error(error) => return self.handle_error(error),
+            }
         }
     }
 
@@ -202,8 +281,55 @@ impl MssqlStream {
     }
 }
 
+// a single token of a TabularResult
+enum Token {
+    Message(Message),
+    EnvChange(EnvChange),
+    Info,
+    Error(ProtocolError),
+}
+
+impl Token {
+    // NOTE: fails if `buf` ends before the token does; `columns` is only valid
+    //       if this succeeds
+    fn get(buf: &mut Bytes, columns: &mut Vec<ColumnData>) -> Result<Self, Error> {
+        let ty = MessageType::get(buf)?;
+
+        let message = match ty {
+            MessageType::EnvChange => return Ok(Token::EnvChange(EnvChange::get(buf)?)),
+
+            MessageType::Info => {
+                let _ = Info::get(buf)?;
+                return Ok(Token::Info);
+            }
+
+            MessageType::Error => return Ok(Token::Error(ProtocolError::get(buf)?)),
+
+            MessageType::Row => Message::Row(Row::get(buf, false, columns)?),
+            MessageType::NbcRow => Message::Row(Row::get(buf, true, columns)?),
+            MessageType::LoginAck => Message::LoginAck(LoginAck::get(buf)?),
+            MessageType::FeatureExtAck => Message::FeatureExtAck(FeatureExtAck::get(buf)?),
+            MessageType::ReturnStatus => Message::ReturnStatus(ReturnStatus::get(buf)?),
+            MessageType::ReturnValue => Message::ReturnValue(ReturnValue::get(buf)?),
+            MessageType::Done => Message::Done(Done::get(buf)?),
+            MessageType::DoneInProc => Message::DoneInProc(Done::get(buf)?),
+            MessageType::DoneProc => Message::DoneProc(Done::get(buf)?),
+            MessageType::Order => Message::Order(Order::get(buf)?),
+
+            MessageType::ColMetaData => {
+                // NOTE: the data gets consumed by the stream for use in
+                //       subsequent Row decoding
+                ColMetaData::get(buf, columns)?;
+                Message::ColMetaData
+            }
+        };
+
+        Ok(Token::Message(message))
+    }
+}
+
 impl Deref for MssqlStream {
-    type Target = BufStream<MaybeTlsStream<TcpStream>>;
+    type Target = BufStream<MaybeTlsStream<TlsPreloginWrapper<TcpStream>>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
@@ -215,3 +341,31 @@ impl DerefMut for MssqlStream {
         &mut self.inner
     }
 }
+
+#[test]
+fn test_get_token_across_packets() {
+    // a DONE token split over two packets
+    let first = Bytes::from_static(&[0xfd, 0x10, 0x00, 0xc1, 0x00, 0x01]);
+    let second = Bytes::from_static(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+    let mut columns = Vec::new();
+
+    assert!(Token::get(&mut first.clone(), &mut columns).is_err());
+
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(&first);
+    buf.extend_from_slice(&second);
+
+    let mut buf = buf.freeze();
+
+    match Token::get(&mut buf, &mut columns) {
+        Ok(Token::Message(Message::Done(done))) => {
+            assert!(done.status.contains(DoneStatus::DONE_COUNT));
+            assert_eq!(done.affected_rows, 1);
+        }
+
+        _ => panic!("expected a DONE token"),
+    }
+
+    assert!(buf.is_empty());
+}
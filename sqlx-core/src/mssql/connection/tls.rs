@@ -0,0 +1,283 @@
+use std::cmp;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::ready;
+use sqlx_rt::{
+    fs,
+    native_tls::{Certificate, TlsConnector},
+    AsyncRead, AsyncWrite,
+};
+
+use crate::error::Error;
+use crate::io::{Decode, Encode};
+use crate::mssql::connection::stream::MssqlStream;
+use crate::mssql::protocol::packet::{PacketHeader, PacketType, Status};
+use crate::mssql::protocol::pre_login::Encrypt;
+use crate::mssql::{MssqlConnectOptions, MssqlEncrypt};
+
+const HEADER_LEN: usize = 8;
+
+// the packet size is only negotiated during login, until then packets are limited to 4 KiB
+const MAX_PACKET_LEN: usize = 4096;
+
+pub(super) async fn maybe_upgrade(
+    stream: &mut MssqlStream,
+    options: &MssqlConnectOptions,
+    server: Encrypt,
+) -> Result<(), Error> {
+    // the client certificate flag only matters to the server
+    let server = server - Encrypt::CLIENT_CERT;
+
+    match options.encrypt {
+        MssqlEncrypt::Disabled => {
+            if server == Encrypt::REQUIRED {
+                return Err(Error::Tls("server requires TLS".into()));
+            }
+        }
+
+        MssqlEncrypt::Preferred => {
+            if server != Encrypt::NOT_SUPPORTED {
+                upgrade(stream, options).await?;
+            }
+        }
+
+        MssqlEncrypt::Required => {
+            if server == Encrypt::NOT_SUPPORTED {
+                return Err(Error::Tls("server does not support TLS".into()));
+            }
+
+            upgrade(stream, options).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn upgrade(stream: &mut MssqlStream, options: &MssqlConnectOptions) -> Result<(), Error> {
+    // FIXME: de-duplicate with postgres/connection/tls.rs
+
+    let accept_invalid_certs =
+        options.encrypt != MssqlEncrypt::Required || options.trust_server_certificate;
+
+    let mut builder = TlsConnector::builder();
+    builder
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .danger_accept_invalid_hostnames(accept_invalid_certs);
+
+    if !accept_invalid_certs {
+        if let Some(ca) = &options.ssl_root_cert {
+            let data = fs::read(ca).await?;
+            let cert = Certificate::from_pem(&data).map_err(Error::tls)?;
+
+            builder.add_root_certificate(cert);
+        }
+    }
+
+    #[cfg(not(feature = "runtime-async-std"))]
+    let connector = builder.build().map_err(Error::tls)?;
+
+    #[cfg(feature = "runtime-async-std")]
+    let connector = builder;
+
+    // the records of the TLS handshake are exchanged inside of PRELOGIN packets; once the
+    // handshake completes, TLS is used directly on the socket for the rest of the connection
+
+    stream.pending_handshake = true;
+    stream.upgrade(&options.host, connector.into()).await?;
+    stream.pending_handshake = false;
+
+    Ok(())
+}
+
+/// Wraps the socket of a connection to tunnel a TLS handshake through PRELOGIN packets.
+///
+/// Outside of a handshake, reads and writes are passed through to the socket as-is.
+pub(crate) struct TlsPreloginWrapper<S> {
+    stream: S,
+
+    pub(crate) pending_handshake: bool,
+
+    // the PRELOGIN packet that handshake records are being collected into and the
+    // number of its bytes that have been written to the socket
+    wbuf: Vec<u8>,
+    wpos: usize,
+
+    // the header of the next PRELOGIN packet from the server and the number of bytes
+    // of the payload of the current packet that are yet to be read
+    header: [u8; HEADER_LEN],
+    header_pos: usize,
+    remaining: usize,
+}
+
+impl<S> TlsPreloginWrapper<S> {
+    pub(crate) fn new(stream: S) -> Self {
+        Self {
+            stream,
+            pending_handshake: false,
+            wbuf: Vec::new(),
+            wpos: 0,
+            header: [0; HEADER_LEN],
+            header_pos: 0,
+            remaining: 0,
+        }
+    }
+}
+
+impl<S> TlsPreloginWrapper<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // writes out the collected PRELOGIN packet, if any
+    fn poll_write_packet(&mut self, cx: &mut Context<'_>, status: Status) -> Poll<io::Result<()>> {
+        if self.wbuf.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+
+        if self.wpos == 0 {
+            let mut header = Vec::with_capacity(HEADER_LEN);
+
+            PacketHeader {
+                r#type: PacketType::PreLogin,
+                status,
+                length: self.wbuf.len() as u16,
+                server_process_id: 0,
+                packet_id: 1,
+            }
+            .encode_with(&mut header, &mut 0);
+
+            self.wbuf[..HEADER_LEN].copy_from_slice(&header);
+        }
+
+        while self.wpos < self.wbuf.len() {
+            let n = ready!(Pin::new(&mut self.stream).poll_write(cx, &self.wbuf[self.wpos..]))?;
+
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+
+            self.wpos += n;
+        }
+
+        self.wbuf.clear();
+        self.wpos = 0;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> AsyncRead for TlsPreloginWrapper<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        if !this.pending_handshake {
+            return Pin::new(&mut this.stream).poll_read(cx, buf);
+        }
+
+        while this.remaining == 0 {
+            while this.header_pos < HEADER_LEN {
+                let header = &mut this.header[this.header_pos..];
+                let n = ready!(Pin::new(&mut this.stream).poll_read(cx, header))?;
+
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+
+                this.header_pos += n;
+            }
+
+            this.header_pos = 0;
+
+            let header = PacketHeader::decode(Bytes::copy_from_slice(&this.header))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+            this.remaining = (header.length as usize)
+                .checked_sub(HEADER_LEN)
+                .ok_or(io::ErrorKind::InvalidData)?;
+        }
+
+        let len = cmp::min(buf.len(), this.remaining);
+        let n = ready!(Pin::new(&mut this.stream).poll_read(cx, &mut buf[..len]))?;
+
+        this.remaining -= n;
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<S> AsyncWrite for TlsPreloginWrapper<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        if !this.pending_handshake {
+            return Pin::new(&mut this.stream).poll_write(cx, buf);
+        }
+
+        // finish writing a packet before starting on the next one
+        if this.wpos > 0 || this.wbuf.len() == MAX_PACKET_LEN {
+            ready!(this.poll_write_packet(cx, Status::NORMAL))?;
+        }
+
+        if this.wbuf.is_empty() {
+            // leave room for the packet header
+            this.wbuf.resize(HEADER_LEN, 0);
+        }
+
+        let len = cmp::min(buf.len(), MAX_PACKET_LEN - this.wbuf.len());
+        this.wbuf.extend_from_slice(&buf[..len]);
+
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+
+        if this.pending_handshake {
+            // a flush marks the end of a flight of handshake records
+            ready!(this.poll_write_packet(cx, Status::END_OF_MESSAGE))?;
+        }
+
+        Pin::new(&mut this.stream).poll_flush(cx)
+    }
+
+    #[cfg(any(feature = "runtime-actix", feature = "runtime-tokio"))]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    #[cfg(feature = "runtime-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_close(cx)
+    }
+}
+
+impl<S> Deref for TlsPreloginWrapper<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl<S> DerefMut for TlsPreloginWrapper<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stream
+    }
+}
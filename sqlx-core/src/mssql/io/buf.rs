@@ -4,6 +4,18 @@ use crate::error::Error;
 use crate::io::BufExt;
 
 pub trait MssqlBufExt: Buf {
+    // like `get_u8` and friends, but return an error instead of panicking when there are not
+    // enough bytes left
+    fn try_get_u8(&mut self) -> Result<u8, Error>;
+
+    fn try_get_u16_le(&mut self) -> Result<u16, Error>;
+
+    fn try_get_u32_le(&mut self) -> Result<u32, Error>;
+
+    fn try_get_u64_le(&mut self) -> Result<u64, Error>;
+
+    fn try_get_bytes(&mut self, n: usize) -> Result<Bytes, Error>;
+
     fn get_utf16_str(&mut self, n: usize) -> Result<String, Error>;
 
     fn get_b_varchar(&mut self) -> Result<String, Error>;
@@ -14,8 +26,35 @@ pub trait MssqlBufExt: Buf {
 }
 
 impl MssqlBufExt for Bytes {
+    fn try_get_u8(&mut self) -> Result<u8, Error> {
+        remaining(self, 1)?;
+        Ok(self.get_u8())
+    }
+
+    fn try_get_u16_le(&mut self) -> Result<u16, Error> {
+        remaining(self, 2)?;
+        Ok(self.get_u16_le())
+    }
+
+    fn try_get_u32_le(&mut self) -> Result<u32, Error> {
+        remaining(self, 4)?;
+        Ok(self.get_u32_le())
+    }
+
+    fn try_get_u64_le(&mut self) -> Result<u64, Error> {
+        remaining(self, 8)?;
+        Ok(self.get_u64_le())
+    }
+
+    fn try_get_bytes(&mut self, n: usize) -> Result<Bytes, Error> {
+        remaining(self, n)?;
+        Ok(self.split_to(n))
+    }
+
     fn get_utf16_str(&mut self, mut n: usize) -> Result<String, Error> {
-        let mut raw = Vec::with_capacity(n * 2);
+        remaining(self, n * 2)?;
+
+        let mut raw = Vec::with_capacity(n);
 
         while n > 0 {
             let ch = self.get_u16_le();
@@ -27,12 +66,12 @@ impl MssqlBufExt for Bytes {
     }
 
     fn get_b_varchar(&mut self) -> Result<String, Error> {
-        let size = self.get_u8();
+        let size = self.try_get_u8()?;
         self.get_utf16_str(size as usize)
     }
 
     fn get_us_varchar(&mut self) -> Result<String, Error> {
-        let size = self.get_u16_le();
+        let size = self.try_get_u16_le()?;
         self.get_utf16_str(size as usize)
     }
 
@@ -41,3 +80,15 @@ impl MssqlBufExt for Bytes {
         self.get_bytes(size as usize)
     }
 }
+
+fn remaining(buf: &Bytes, n: usize) -> Result<(), Error> {
+    if buf.len() < n {
+        return Err(err_protocol!(
+            "unexpected end of data: expected {} more bytes but only {} remain",
+            n,
+            buf.len()
+        ));
+    }
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ mod database;
 mod error;
 mod io;
 mod options;
+mod procedure;
 mod protocol;
 mod row;
 mod transaction;
@@ -17,9 +18,10 @@ pub use arguments::MssqlArguments;
 pub use connection::MssqlConnection;
 pub use database::Mssql;
 pub use error::MssqlDatabaseError;
-pub use options::MssqlConnectOptions;
+pub use options::{MssqlConnectOptions, MssqlEncrypt};
+pub use procedure::{MssqlProcedureCall, MssqlProcedureResult};
 pub use row::MssqlRow;
-pub use transaction::MssqlTransactionManager;
+pub use transaction::{MssqlIsolationLevel, MssqlTransactionManager};
 pub use type_info::MssqlTypeInfo;
 pub use value::{MssqlValue, MssqlValueRef};
 
@@ -0,0 +1,213 @@
+use either::Either;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::error::Error;
+use crate::mssql::protocol::done::Status;
+use crate::mssql::protocol::message::Message;
+use crate::mssql::protocol::packet::PacketType;
+use crate::mssql::protocol::rpc::{OptionFlags, RpcRequest};
+use crate::mssql::{Mssql, MssqlArguments, MssqlConnection, MssqlRow, MssqlTypeInfo, MssqlValue};
+use crate::types::Type;
+use crate::value::Value;
+
+/// A call to a stored procedure, built with [`MssqlConnection::call`].
+///
+/// Parameters are either passed by position, with [`bind`](Self::bind), or by name, with
+/// [`bind_named`](Self::bind_named) and [`bind_output`](Self::bind_output). Once a parameter
+/// has been passed by name, the parameters that follow it must be passed by name as well.
+pub struct MssqlProcedureCall<'c> {
+    conn: &'c mut MssqlConnection,
+    name: String,
+    arguments: MssqlArguments,
+}
+
+/// The result sets, output parameters and return status of a call to a stored procedure.
+#[derive(Default)]
+pub struct MssqlProcedureResult {
+    result_sets: Vec<Vec<MssqlRow>>,
+    rows_affected: u64,
+    output: Vec<(String, MssqlValue)>,
+    return_status: Option<i32>,
+}
+
+impl MssqlConnection {
+    /// Starts a call to the stored procedure with the given name.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::mssql::MssqlConnection;
+    /// # async fn example(conn: &mut MssqlConnection) -> Result<(), sqlx_core::error::Error> {
+    /// let result = conn
+    ///     .call("dbo.transfer")
+    ///     .bind_named("@amount", 100_i32)
+    ///     .bind_output("@balance", 0_i32)
+    ///     .execute()
+    ///     .await?;
+    ///
+    /// let balance: i32 = result.try_get_output("@balance")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call(&mut self, name: &str) -> MssqlProcedureCall<'_> {
+        MssqlProcedureCall {
+            conn: self,
+            name: name.to_owned(),
+            arguments: MssqlArguments::default(),
+        }
+    }
+}
+
+impl<'c> MssqlProcedureCall<'c> {
+    /// Passes an input parameter by position.
+    pub fn bind<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(mut self, value: T) -> Self {
+        self.arguments.add_unnamed(value);
+        self
+    }
+
+    /// Passes an input parameter by name, with or without the leading `@`.
+    pub fn bind_named<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        self.arguments.add_named(&param_name(name), value);
+        self
+    }
+
+    /// Passes an output parameter by name, with or without the leading `@`.
+    ///
+    /// The value is passed in to the procedure as the initial value of the parameter and
+    /// decides its type. The value the procedure leaves in the parameter can be read with
+    /// [`MssqlProcedureResult::try_get_output`].
+    pub fn bind_output<'q, T: 'q + Encode<'q, Mssql> + Type<Mssql>>(
+        mut self,
+        name: &str,
+        value: T,
+    ) -> Self {
+        self.arguments.declare(&param_name(name), value);
+        self
+    }
+
+    /// Calls the procedure and collects everything it returns.
+    pub async fn execute(self) -> Result<MssqlProcedureResult, Error> {
+        let stream = &mut self.conn.stream;
+
+        stream.wait_until_ready().await?;
+        stream.pending_done_count += 1;
+
+        stream.write_packet(
+            PacketType::Rpc,
+            RpcRequest {
+                transaction_descriptor: stream.transaction_descriptor,
+                arguments: &self.arguments,
+                procedure: Either::Left(&self.name),
+                options: OptionFlags::empty(),
+            },
+        );
+
+        stream.flush().await?;
+
+        let mut result = MssqlProcedureResult::default();
+
+        loop {
+            match stream.recv_message().await? {
+                Message::ColMetaData => {
+                    result.result_sets.push(Vec::new());
+                }
+
+                Message::Row(row) => {
+                    if result.result_sets.is_empty() {
+                        result.result_sets.push(Vec::new());
+                    }
+
+                    // unwrap: a result set was pushed above
+                    result
+                        .result_sets
+                        .last_mut()
+                        .unwrap()
+                        .push(MssqlRow { row });
+                }
+
+                Message::ReturnValue(value) => {
+                    result.output.push((
+                        value.param_name,
+                        MssqlValue {
+                            type_info: MssqlTypeInfo(value.type_info),
+                            data: value.value,
+                        },
+                    ));
+                }
+
+                Message::ReturnStatus(status) => {
+                    result.return_status = Some(status.value);
+                }
+
+                Message::DoneInProc(done) if done.status.contains(Status::DONE_COUNT) => {
+                    result.rows_affected += done.affected_rows;
+                }
+
+                Message::Done(done) | Message::DoneProc(done) => {
+                    if done.status.contains(Status::DONE_COUNT) {
+                        result.rows_affected += done.affected_rows;
+                    }
+
+                    if !done.status.contains(Status::DONE_MORE) {
+                        stream.handle_done(&done);
+                        break;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl MssqlProcedureResult {
+    /// Returns the result sets of the procedure, in the order they were returned.
+    pub fn result_sets(&self) -> &[Vec<MssqlRow>] {
+        &self.result_sets
+    }
+
+    /// Returns the rows of all the result sets of the procedure.
+    pub fn rows(&self) -> impl Iterator<Item = &MssqlRow> {
+        self.result_sets.iter().flatten()
+    }
+
+    /// Returns the total number of rows affected by the statements of the procedure.
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// Returns the value of the `RETURN` statement of the procedure, if any.
+    pub fn return_status(&self) -> Option<i32> {
+        self.return_status
+    }
+
+    /// Decodes the value of an output parameter by its name, with or without the leading `@`.
+    pub fn try_get_output<'r, T>(&'r self, name: &str) -> Result<T, Error>
+    where
+        T: Decode<'r, Mssql> + Type<Mssql>,
+    {
+        let name = name.trim_start_matches('@');
+
+        self.output
+            .iter()
+            .find(|(param, _)| param.trim_start_matches('@').eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::ColumnNotFound(name.into()))?
+            .1
+            .try_decode()
+    }
+}
+
+fn param_name(name: &str) -> String {
+    if name.starts_with('@') {
+        name.to_owned()
+    } else {
+        format!("@{}", name)
+    }
+}
@@ -1,9 +1,9 @@
 use bitflags::bitflags;
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 
 use crate::error::Error;
 use crate::mssql::io::MssqlBufExt;
-use crate::mssql::protocol::type_info::TypeInfo;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
 
 #[derive(Debug)]
 pub(crate) struct ColMetaData;
@@ -18,7 +18,10 @@ pub(crate) struct ColumnData {
     pub(crate) flags: Flags,
     pub(crate) type_info: TypeInfo,
 
-    // TODO: pub(crate) table_name: Option<Vec<String>>,
+    // The fully qualified name of the table that a text, ntext or image column belongs to,
+    // as a list of up to 4 parts.
+    pub(crate) table_name: Option<Vec<String>>,
+
     // TODO: crypto_meta_data: Option<CryptoMetaData>,
 
     // The column name. It contains the column name length and column name.
@@ -76,7 +79,7 @@ impl ColMetaData {
     pub(crate) fn get(buf: &mut Bytes, columns: &mut Vec<ColumnData>) -> Result<(), Error> {
         columns.clear();
 
-        let mut count = buf.get_u16_le();
+        let mut count = buf.try_get_u16_le()?;
 
         if count == 0xffff {
             // In the event that the client requested no metadata to be returned, the value of
@@ -98,11 +101,25 @@ impl ColMetaData {
 
 impl ColumnData {
     fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let user_type = buf.get_u32_le();
-        let flags = Flags::from_bits_truncate(buf.get_u16_le());
+        let user_type = buf.try_get_u32_le()?;
+        let flags = Flags::from_bits_truncate(buf.try_get_u16_le()?);
         let type_info = TypeInfo::get(buf)?;
 
-        // TODO: table_name
+        let table_name = match type_info.ty {
+            DataType::Text | DataType::NText | DataType::Image => {
+                let num_parts = buf.try_get_u8()?;
+                let mut parts = Vec::with_capacity(num_parts as usize);
+
+                for _ in 0..num_parts {
+                    parts.push(buf.get_us_varchar()?);
+                }
+
+                Some(parts)
+            }
+
+            _ => None,
+        };
+
         // TODO: crypto_meta_data
 
         let name = buf.get_b_varchar()?;
@@ -111,7 +128,27 @@ impl ColumnData {
             user_type,
             flags,
             type_info,
+            table_name,
             col_name: name,
         })
     }
 }
+
+#[test]
+fn test_get_col_meta_data_out_of_bounds() {
+    // a single TEXT column whose table name is cut short
+    #[rustfmt::skip]
+    let data = [
+        0x01, 0x00,                         // [Count]
+        0x00, 0x00, 0x00, 0x00,             // [UserType]
+        0x09, 0x00,                         // [Flags]
+        0x23, 0x10, 0x00, 0x00, 0x00,       // [TYPE_INFO] TEXT
+        0x09, 0x04, 0xd0, 0x00, 0x34,       // [Collation]
+        0x02,                               // [NumParts]
+        0x03, 0x00, b'd', 0x00, b'b', 0x00, // [PartName]
+    ];
+
+    let mut columns = Vec::new();
+
+    assert!(ColMetaData::get(&mut Bytes::copy_from_slice(&data), &mut columns).is_err());
+}
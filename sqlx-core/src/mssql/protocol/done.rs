@@ -1,7 +1,8 @@
 use bitflags::bitflags;
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 
 use crate::error::Error;
+use crate::mssql::io::MssqlBufExt;
 
 #[derive(Debug)]
 pub(crate) struct Done {
@@ -18,9 +19,9 @@ pub(crate) struct Done {
 
 impl Done {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let status = Status::from_bits_truncate(buf.get_u16_le());
-        let cursor_command = buf.get_u16_le();
-        let affected_rows = buf.get_u64_le();
+        let status = Status::from_bits_truncate(buf.try_get_u16_le()?);
+        let cursor_command = buf.try_get_u16_le()?;
+        let affected_rows = buf.try_get_u64_le()?;
 
         Ok(Self {
             affected_rows,
@@ -33,9 +33,9 @@ pub(crate) enum EnvChange {
 
 impl EnvChange {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let len = buf.get_u16_le();
-        let ty = buf.get_u8();
-        let mut data = buf.split_to((len - 1) as usize);
+        let len = buf.try_get_u16_le()?;
+        let mut data = buf.try_get_bytes(len as usize)?;
+        let ty = data.try_get_u8()?;
 
         Ok(match ty {
             1 => EnvChange::Database(data.get_b_varchar()?),
@@ -30,8 +30,8 @@ pub(crate) struct Error {
 
 impl Error {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, crate::error::Error> {
-        let len = buf.get_u16_le();
-        let mut data = buf.split_to(len as usize);
+        let len = buf.try_get_u16_le()?;
+        let mut data = buf.try_get_bytes(len as usize)?;
 
         let number = data.get_i32_le();
         let state = data.get_u8();
@@ -0,0 +1,168 @@
+use bytes::{Buf, Bytes};
+
+use crate::error::Error;
+
+// the FeatureId that ends the list of features, in both LOGIN7 and FEATUREEXTACK
+const TERMINATOR: u8 = 0xff;
+
+/// The optional features that a client can ask for in the `FeatureExt` block of `LOGIN7`,
+/// and that the server acknowledges with a `FEATUREEXTACK` token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum FeatureId {
+    SessionRecovery = 0x01,
+    FedAuth = 0x02,
+    ColumnEncryption = 0x04,
+    GlobalTransactions = 0x05,
+    AzureSqlSupport = 0x08,
+    DataClassification = 0x09,
+
+    // lets the server send VARCHAR data in UTF-8 collations as is, instead of
+    // translating it to the code page of a non-UTF-8 collation
+    Utf8Support = 0x0a,
+}
+
+impl FeatureId {
+    fn get(id: u8) -> Option<Self> {
+        Some(match id {
+            0x01 => FeatureId::SessionRecovery,
+            0x02 => FeatureId::FedAuth,
+            0x04 => FeatureId::ColumnEncryption,
+            0x05 => FeatureId::GlobalTransactions,
+            0x08 => FeatureId::AzureSqlSupport,
+            0x09 => FeatureId::DataClassification,
+            0x0a => FeatureId::Utf8Support,
+
+            _ => {
+                return None;
+            }
+        })
+    }
+}
+
+// writes the FeatureExt block of LOGIN7
+//
+// NOTE: none of the features we ask for carry any data of their own
+pub(crate) fn put_feature_ext(buf: &mut Vec<u8>, features: &[FeatureId]) {
+    for &feature in features {
+        buf.push(feature as u8);
+        buf.extend(&0_u32.to_le_bytes());
+    }
+
+    buf.push(TERMINATOR);
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+pub(crate) enum FeatureAck {
+    // the state of the session that is needed to recover the connection, should it break
+    SessionRecovery(Bytes),
+
+    // the highest version of Always Encrypted that the server supports
+    ColumnEncryption { version: u8 },
+
+    Utf8Support { enabled: bool },
+
+    // a feature we know about but have no use for its data
+    Other { id: FeatureId, data: Bytes },
+}
+
+/// The features of `LOGIN7` that the server acknowledges, sent once the login succeeds.
+#[derive(Debug)]
+pub(crate) struct FeatureExtAck {
+    pub(crate) features: Vec<FeatureAck>,
+}
+
+impl FeatureExtAck {
+    pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
+        let mut features = Vec::new();
+
+        loop {
+            if !buf.has_remaining() {
+                return Err(err_protocol!("FEATUREEXTACK: missing terminator"));
+            }
+
+            let id = buf.get_u8();
+
+            if id == TERMINATOR {
+                break;
+            }
+
+            if buf.remaining() < 4 {
+                return Err(err_protocol!("FEATUREEXTACK: truncated feature"));
+            }
+
+            let len = buf.get_u32_le() as usize;
+
+            if buf.remaining() < len {
+                return Err(err_protocol!("FEATUREEXTACK: truncated feature data"));
+            }
+
+            let data = buf.split_to(len);
+
+            features.push(match FeatureId::get(id) {
+                Some(FeatureId::SessionRecovery) => FeatureAck::SessionRecovery(data),
+
+                Some(FeatureId::ColumnEncryption) if !data.is_empty() => {
+                    FeatureAck::ColumnEncryption { version: data[0] }
+                }
+
+                Some(FeatureId::Utf8Support) if !data.is_empty() => FeatureAck::Utf8Support {
+                    enabled: data[0] & 0x01 != 0,
+                },
+
+                Some(id) => FeatureAck::Other { id, data },
+
+                None => {
+                    return Err(err_protocol!("FEATUREEXTACK: unknown feature 0x{:02x}", id));
+                }
+            });
+        }
+
+        Ok(Self { features })
+    }
+}
+
+#[test]
+fn test_get_feature_ext_ack() {
+    #[rustfmt::skip]
+    let mut buf = Bytes::from_static(&[
+        0x01, 0x03, 0x00, 0x00, 0x00, 0xAA, 0xBB, 0xCC, // SESSIONRECOVERY
+        0x04, 0x01, 0x00, 0x00, 0x00, 0x02,             // COLUMNENCRYPTION
+        0x09, 0x02, 0x00, 0x00, 0x00, 0x02, 0x00,       // DATACLASSIFICATION
+        0x0A, 0x01, 0x00, 0x00, 0x00, 0x01,             // UTF8_SUPPORT
+        0xFF,
+    ]);
+
+    let ack = FeatureExtAck::get(&mut buf).unwrap();
+
+    assert!(!buf.has_remaining());
+    assert_eq!(ack.features.len(), 4);
+
+    assert!(matches!(
+        &ack.features[0],
+        FeatureAck::SessionRecovery(data) if data.as_ref() == [0xAA, 0xBB, 0xCC]
+    ));
+
+    assert!(matches!(
+        ack.features[1],
+        FeatureAck::ColumnEncryption { version: 2 }
+    ));
+
+    assert!(matches!(
+        ack.features[2],
+        FeatureAck::Other {
+            id: FeatureId::DataClassification,
+            ..
+        }
+    ));
+
+    assert!(matches!(
+        ack.features[3],
+        FeatureAck::Utf8Support { enabled: true }
+    ));
+
+    let mut buf = Bytes::from_static(&[0x0A, 0x01, 0x00, 0x00]);
+
+    assert!(FeatureExtAck::get(&mut buf).is_err());
+}
@@ -16,8 +16,8 @@ pub(crate) struct Info {
 
 impl Info {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let len = buf.get_u16_le();
-        let mut data = buf.split_to(len as usize);
+        let len = buf.try_get_u16_le()?;
+        let mut data = buf.try_get_bytes(len as usize)?;
 
         let number = data.get_u32_le();
         let state = data.get_u8();
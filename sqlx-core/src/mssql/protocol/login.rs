@@ -1,5 +1,6 @@
 use crate::io::Encode;
 use crate::mssql::io::MssqlBufMutExt;
+use crate::mssql::protocol::feature_ext::{put_feature_ext, FeatureId};
 
 #[derive(Debug)]
 pub struct Login7<'a> {
@@ -16,6 +17,7 @@ pub struct Login7<'a> {
     pub language: &'a str,
     pub database: &'a str,
     pub client_id: [u8; 6],
+    pub features: &'a [FeatureId],
 }
 
 impl Encode<'_> for Login7<'_> {
@@ -70,7 +72,11 @@ impl Encode<'_> for Login7<'_> {
         //    2 | <fUserInstance>
         //    1 | <fSendYukonBinaryXML>
         //    0 | <fChangePassword>
-        buf.push(0);
+        buf.push(if self.features.is_empty() {
+            0
+        } else {
+            0b1_00_00
+        });
 
         // [ClientTimeZone] This field is not used and can be set to zero.
         buf.extend(&0_u32.to_le_bytes());
@@ -108,9 +114,19 @@ impl Encode<'_> for Login7<'_> {
         // [ServerName] The server name
         write_str(buf, &mut offsets, beg, self.server_name);
 
-        // [Extension] Points to an extension block.
-        // TODO: Implement to get FeatureExt which should let us use UTF-8
+        // [Extension] Points to an extension block, which holds the offset of the FeatureExt
+        //   block that is written at the end of the structure
         write_offset(buf, &mut offsets, beg);
+
+        let feature_ext_offset = if self.features.is_empty() {
+            None
+        } else {
+            buf[offsets..(offsets + 2)].copy_from_slice(&4_u16.to_le_bytes());
+            buf.extend(&0_u32.to_le_bytes());
+
+            Some(buf.len() - 4)
+        };
+
         offsets += 2;
 
         // [CltIntName] The interface library name
@@ -137,6 +153,14 @@ impl Encode<'_> for Login7<'_> {
         // [ChangePassword] New password for the specified login
         write_offset(buf, &mut offsets, beg);
 
+        // [FeatureExt] The optional features the client asks the server for
+        if let Some(offset) = feature_ext_offset {
+            let feature_ext = (buf.len() - beg) as u32;
+            buf[offset..(offset + 4)].copy_from_slice(&feature_ext.to_le_bytes());
+
+            put_feature_ext(buf, self.features);
+        }
+
         // Establish the length of the entire structure
         let len = buf.len();
         buf[beg..beg + 4].copy_from_slice(&((len - beg) as u32).to_le_bytes());
@@ -158,7 +182,8 @@ fn write_str(buf: &mut Vec<u8>, offsets: &mut usize, beg: usize, s: &str) {
     write_offset(buf, offsets, beg);
 
     // Write the length, in UCS-2 characters
-    buf[*offsets..(*offsets + 2)].copy_from_slice(&(s.len() as u16).to_le_bytes());
+    let len = s.encode_utf16().count() as u16;
+    buf[*offsets..(*offsets + 2)].copy_from_slice(&len.to_le_bytes());
     *offsets += 2;
 
     // Encode the character sequence as UCS-2 (precursor to UTF16-LE)
@@ -183,6 +208,7 @@ fn test_encode_login() {
         language: "",
         database: "",
         client_id: [0x00, 0x50, 0x8B, 0xE2, 0xB7, 0x8F],
+        features: &[],
     };
 
     // Adapted from v20191101 of MS-TDS
@@ -241,3 +267,97 @@ fn test_encode_login() {
 
     assert_eq!(expected, buf);
 }
+
+#[test]
+fn test_encode_login_with_feature_ext() {
+    let mut buf = Vec::new();
+
+    let login = Login7 {
+        version: 0x74000004,
+        client_program_version: 0,
+        client_pid: 0,
+        packet_size: 0x1000,
+        hostname: "",
+        username: "sa",
+        password: "",
+        app_name: "",
+        server_name: "",
+        client_interface_name: "",
+        language: "",
+        database: "",
+        client_id: [0; 6],
+        features: &[FeatureId::Utf8Support],
+    };
+
+    login.encode(&mut buf);
+
+    // [OptionFlags3] fExtension
+    assert_eq!(buf[27], 0b1_00_00);
+
+    // [ibExtension] [cbExtension]
+    let ib_extension = u16::from_le_bytes([buf[56], buf[57]]) as usize;
+    assert_eq!(u16::from_le_bytes([buf[58], buf[59]]), 4);
+
+    // the extension points to the FeatureExt block, at the very end
+    let mut ib_feature_ext = [0; 4];
+    ib_feature_ext.copy_from_slice(&buf[ib_extension..(ib_extension + 4)]);
+    let ib_feature_ext = u32::from_le_bytes(ib_feature_ext) as usize;
+
+    assert_eq!(
+        &buf[ib_feature_ext..],
+        &[0x0A, 0x00, 0x00, 0x00, 0x00, 0xFF]
+    );
+
+    assert_eq!(
+        u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize,
+        buf.len()
+    );
+}
+
+#[test]
+fn test_encode_login_with_non_ascii_strings() {
+    let mut buf = Vec::new();
+
+    let login = Login7 {
+        version: 0x74000004,
+        client_program_version: 0,
+        client_pid: 0,
+        packet_size: 0x1000,
+        hostname: "",
+        username: "Jürgen",
+        password: "",
+        app_name: "",
+        server_name: "",
+        client_interface_name: "",
+        language: "",
+        // a character outside of the BMP takes two UTF-16 code units
+        database: "データ😀",
+        client_id: [0; 6],
+        features: &[],
+    };
+
+    login.encode(&mut buf);
+
+    let get_u16 = |i: usize| u16::from_le_bytes([buf[i], buf[i + 1]]) as usize;
+
+    // [ibUserName] [cchUserName]
+    let ib_user_name = get_u16(40);
+    assert_eq!(get_u16(42), 6);
+
+    // [ibPassword] follows the 6 characters of the user name
+    assert_eq!(get_u16(44), ib_user_name + 12);
+
+    let user_name: Vec<u16> = buf[ib_user_name..(ib_user_name + 12)]
+        .chunks(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+
+    assert_eq!(String::from_utf16(&user_name).unwrap(), "Jürgen");
+
+    // [ibDatabase] [cchDatabase]
+    let ib_database = get_u16(68);
+    assert_eq!(get_u16(70), 5);
+
+    // [ibSSPI] follows the 5 code units of the database
+    assert_eq!(get_u16(78), ib_database + 10);
+}
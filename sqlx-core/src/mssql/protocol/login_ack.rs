@@ -14,8 +14,8 @@ pub(crate) struct LoginAck {
 
 impl LoginAck {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let len = buf.get_u16_le();
-        let mut data = buf.split_to(len as usize);
+        let len = buf.try_get_u16_le()?;
+        let mut data = buf.try_get_bytes(len as usize)?;
 
         let interface = data.get_u8();
         let tds_version = data.get_u32_le();
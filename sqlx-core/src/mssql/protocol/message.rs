@@ -1,6 +1,7 @@
 use bytes::{Buf, Bytes};
 
 use crate::mssql::protocol::done::Done;
+use crate::mssql::protocol::feature_ext::FeatureExtAck;
 use crate::mssql::protocol::login_ack::LoginAck;
 use crate::mssql::protocol::order::Order;
 use crate::mssql::protocol::return_status::ReturnStatus;
@@ -10,6 +11,7 @@ use crate::mssql::protocol::row::Row;
 #[derive(Debug)]
 pub(crate) enum Message {
     LoginAck(LoginAck),
+    FeatureExtAck(FeatureExtAck),
     Done(Done),
     DoneInProc(Done),
     DoneProc(Done),
@@ -17,12 +19,16 @@ pub(crate) enum Message {
     ReturnStatus(ReturnStatus),
     ReturnValue(ReturnValue),
     Order(Order),
+
+    // the columns themselves are kept by the stream for decoding the rows that follow
+    ColMetaData,
 }
 
 #[derive(Debug)]
 pub(crate) enum MessageType {
     Info,
     LoginAck,
+    FeatureExtAck,
     EnvChange,
     Done,
     DoneProc,
@@ -44,6 +50,7 @@ impl MessageType {
             0xab => MessageType::Info,
             0xac => MessageType::ReturnValue,
             0xad => MessageType::LoginAck,
+            0xae => MessageType::FeatureExtAck,
             0xd1 => MessageType::Row,
             0xd2 => MessageType::NbcRow,
             0xe3 => MessageType::EnvChange,
@@ -2,6 +2,7 @@ pub(crate) mod col_meta_data;
 pub(crate) mod done;
 pub(crate) mod env_change;
 pub(crate) mod error;
+pub(crate) mod feature_ext;
 pub(crate) mod header;
 pub(crate) mod info;
 pub(crate) mod login;
@@ -15,4 +16,5 @@ pub(crate) mod return_value;
 pub(crate) mod row;
 pub(crate) mod rpc;
 pub(crate) mod sql_batch;
+pub(crate) mod transaction_manager;
 pub(crate) mod type_info;
@@ -1,6 +1,7 @@
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 
 use crate::error::Error;
+use crate::mssql::io::MssqlBufExt;
 
 #[derive(Debug)]
 pub(crate) struct Order {
@@ -9,8 +10,8 @@ pub(crate) struct Order {
 
 impl Order {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let len = buf.get_u16_le();
-        let columns = buf.split_to(len as usize);
+        let len = buf.try_get_u16_le()?;
+        let columns = buf.try_get_bytes(len as usize)?;
 
         Ok(Self { columns })
     }
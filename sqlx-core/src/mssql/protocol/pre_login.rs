@@ -24,58 +24,97 @@ impl<'de> Decode<'de> for PreLogin<'de> {
     fn decode_with(buf: Bytes, _: ()) -> Result<Self, Error> {
         let mut version = None;
         let mut encryption = None;
-
-        // TODO: Decode the remainder of the structure
-        // let mut instance = None;
-        // let mut thread_id = None;
-        // let mut trace_id = None;
-        // let mut multiple_active_result_sets = None;
+        let mut thread_id = None;
+        let mut trace_id = None;
+        let mut multiple_active_result_sets = None;
 
         let mut offsets = buf.clone();
 
         loop {
+            if !offsets.has_remaining() {
+                return Err(err_protocol!("PRELOGIN: missing option terminator"));
+            }
+
             let token = offsets.get_u8();
 
+            if token == 0xff {
+                break;
+            }
+
+            if offsets.remaining() < 4 {
+                return Err(err_protocol!(
+                    "PRELOGIN: unexpected end of option 0x{:02x}",
+                    token
+                ));
+            }
+
+            let offset = offsets.get_u16() as usize;
+            let size = offsets.get_u16() as usize;
+
+            let mut data = buf.get(offset..offset + size).ok_or_else(|| {
+                err_protocol!(
+                    "PRELOGIN: option 0x{:02x} at offset {} with length {} is out of bounds",
+                    token,
+                    offset,
+                    size
+                )
+            })?;
+
             match PreLoginOptionToken::get(token) {
-                Some(token) => {
-                    let offset = offsets.get_u16() as usize;
-                    let size = offsets.get_u16() as usize;
-                    let mut data = &buf[offset..offset + size];
-
-                    match token {
-                        PreLoginOptionToken::Version => {
-                            let major = data.get_u8();
-                            let minor = data.get_u8();
-                            let build = data.get_u16();
-                            let sub_build = data.get_u16();
-
-                            version = Some(Version {
-                                major,
-                                minor,
-                                build,
-                                sub_build,
-                            });
-                        }
-
-                        PreLoginOptionToken::Encryption => {
-                            encryption = Some(Encrypt::from_bits_truncate(data.get_u8()));
-                        }
-
-                        tok => todo!("{:?}", tok),
-                    }
+                Some(PreLoginOptionToken::Version) if size >= 6 => {
+                    let major = data.get_u8();
+                    let minor = data.get_u8();
+                    let build = data.get_u16();
+                    let sub_build = data.get_u16();
+
+                    version = Some(Version {
+                        major,
+                        minor,
+                        build,
+                        sub_build,
+                    });
                 }
 
-                None if token == 0xff => {
-                    break;
+                Some(PreLoginOptionToken::Encryption) if size >= 1 => {
+                    encryption = Some(Encrypt::from_bits_truncate(data.get_u8()));
                 }
 
-                None => {
+                Some(PreLoginOptionToken::ThreadId) if size >= 4 => {
+                    thread_id = Some(data.get_u32_le());
+                }
+
+                Some(PreLoginOptionToken::MultipleActiveResultSets) if size >= 1 => {
+                    multiple_active_result_sets = Some(data.get_u8() != 0);
+                }
+
+                Some(PreLoginOptionToken::TraceId) if size >= 36 => {
+                    let connection_id = Uuid::from_slice(&data[..16]).map_err(Error::protocol)?;
+                    let activity_id = Uuid::from_slice(&data[16..32]).map_err(Error::protocol)?;
+                    data.advance(32);
+
+                    trace_id = Some(TraceId {
+                        connection_id,
+                        activity_id,
+                        activity_seq: data.get_u32(),
+                    });
+                }
+
+                Some(PreLoginOptionToken::Version)
+                | Some(PreLoginOptionToken::Encryption)
+                | Some(PreLoginOptionToken::MultipleActiveResultSets)
+                | Some(PreLoginOptionToken::TraceId) => {
                     return Err(err_protocol!(
-                        "PRELOGIN: unexpected login option token: 0x{:02?}",
-                        token
-                    )
-                    .into());
+                        "PRELOGIN: option 0x{:02x} has an invalid length of {}",
+                        token,
+                        size
+                    ));
                 }
+
+                // the server answers INSTANCE with whether the instance name matched, that the
+                // name was validated at all is of no interest to us; the remaining options are
+                // only sent by servers that support federated authentication or that reply with
+                // an empty THREADID
+                _ => {}
             }
         }
 
@@ -89,6 +128,9 @@ impl<'de> Decode<'de> for PreLogin<'de> {
         Ok(Self {
             version,
             encryption,
+            thread_id,
+            trace_id,
+            multiple_active_result_sets,
 
             ..Default::default()
         })
@@ -142,7 +184,7 @@ impl Encode<'_> for PreLogin<'_> {
         }
 
         if let Some(trace) = &self.trace_id {
-            ThreadId.put(buf, &mut offsets, &mut offset, 36);
+            TraceId.put(buf, &mut offsets, &mut offset, 36);
             buf.extend_from_slice(trace.connection_id.as_bytes());
             buf.extend_from_slice(trace.activity_id.as_bytes());
             buf.extend_from_slice(&trace.activity_seq.to_be_bytes());
@@ -168,6 +210,11 @@ enum PreLoginOptionToken {
     MultipleActiveResultSets = 0x04,
 
     TraceId = 0x05,
+
+    // whether the server requires the client to authenticate with federated authentication
+    FedAuthRequired = 0x06,
+
+    Nonce = 0x07,
 }
 
 impl PreLoginOptionToken {
@@ -192,6 +239,8 @@ impl PreLoginOptionToken {
             0x03 => PreLoginOptionToken::ThreadId,
             0x04 => PreLoginOptionToken::MultipleActiveResultSets,
             0x05 => PreLoginOptionToken::TraceId,
+            0x06 => PreLoginOptionToken::FedAuthRequired,
+            0x07 => PreLoginOptionToken::Nonce,
 
             _ => {
                 return None;
@@ -243,6 +292,9 @@ bitflags! {
     /// wire encryption to be used.
     #[derive(Default)]
     pub(crate) struct Encrypt: u8 {
+        /// Encryption is available but off.
+        const OFF = 0x00;
+
         /// Encryption is available but on.
         const ON = 0x01;
 
@@ -309,3 +361,32 @@ fn test_decode_pre_login() {
     // ENCRYPT_OFF
     assert_eq!(pre_login.encryption.bits(), 0);
 }
+
+#[test]
+fn test_decode_pre_login_with_unknown_options() {
+    #[rustfmt::skip]
+    let buffer = Bytes::from_static(&[
+        0, 0, 36, 0, 6, 1, 0, 42, 0, 1, 2, 0, 43, 0, 1, 3, 0, 44, 0, 0,
+        4, 0, 44, 0, 1, 6, 0, 45, 0, 1, 0x42, 0, 46, 0, 0, 255,
+        15, 0, 7, 208, 0, 0, 2, 0, 1, 0,
+    ]);
+
+    let pre_login = PreLogin::decode(buffer).unwrap();
+
+    assert_eq!(pre_login.version.major, 15);
+    assert_eq!(pre_login.version.build, 2000);
+    assert_eq!(pre_login.encryption, Encrypt::NOT_SUPPORTED);
+    assert_eq!(pre_login.thread_id, None);
+    assert_eq!(pre_login.multiple_active_result_sets, Some(true));
+}
+
+#[test]
+fn test_decode_pre_login_out_of_bounds() {
+    #[rustfmt::skip]
+    let buffer = Bytes::from_static(&[
+        0, 0, 11, 0, 6, 1, 0, 17, 0, 1, 255,
+        14, 0, 12, 209,
+    ]);
+
+    assert!(PreLogin::decode(buffer).is_err());
+}
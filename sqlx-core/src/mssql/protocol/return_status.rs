@@ -1,15 +1,16 @@
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 
 use crate::error::Error;
+use crate::mssql::io::MssqlBufExt;
 
 #[derive(Debug)]
 pub(crate) struct ReturnStatus {
-    value: i32,
+    pub(crate) value: i32,
 }
 
 impl ReturnStatus {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let value = buf.get_i32_le();
+        let value = buf.try_get_u32_le()? as i32;
 
         Ok(Self { value })
     }
@@ -1,5 +1,5 @@
 use bitflags::bitflags;
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 
 use crate::error::Error;
 use crate::mssql::io::MssqlBufExt;
@@ -9,12 +9,12 @@ use crate::mssql::protocol::type_info::TypeInfo;
 #[derive(Debug)]
 pub(crate) struct ReturnValue {
     param_ordinal: u16,
-    param_name: String,
+    pub(crate) param_name: String,
     status: ReturnValueStatus,
     user_type: u32,
     flags: Flags,
-    type_info: TypeInfo,
-    value: Option<Bytes>,
+    pub(crate) type_info: TypeInfo,
+    pub(crate) value: Option<Bytes>,
 }
 
 bitflags! {
@@ -29,13 +29,13 @@ bitflags! {
 
 impl ReturnValue {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        let ordinal = buf.get_u16_le();
+        let ordinal = buf.try_get_u16_le()?;
         let name = buf.get_b_varchar()?;
-        let status = ReturnValueStatus::from_bits_truncate(buf.get_u8());
-        let user_type = buf.get_u32_le();
-        let flags = Flags::from_bits_truncate(buf.get_u16_le());
+        let status = ReturnValueStatus::from_bits_truncate(buf.try_get_u8()?);
+        let user_type = buf.try_get_u32_le()?;
+        let flags = Flags::from_bits_truncate(buf.try_get_u16_le()?);
         let type_info = TypeInfo::get(buf)?;
-        let value = type_info.get_value(buf);
+        let value = type_info.get_value(buf)?;
 
         Ok(Self {
             param_ordinal: ordinal,
@@ -1,7 +1,7 @@
 use bytes::Bytes;
 
 use crate::error::Error;
-use crate::io::BufExt;
+use crate::mssql::io::MssqlBufExt;
 use crate::mssql::protocol::col_meta_data::ColumnData;
 use crate::mssql::MssqlTypeInfo;
 
@@ -23,7 +23,7 @@ impl Row {
         let mut column_types = Vec::with_capacity(columns.len());
 
         let nulls = if nullable {
-            buf.get_bytes((columns.len() + 7) / 8)
+            buf.try_get_bytes((columns.len() + 7) / 8)?
         } else {
             Bytes::from_static(b"")
         };
@@ -32,7 +32,7 @@ impl Row {
             column_types.push(MssqlTypeInfo(column.type_info.clone()));
 
             if !(column.type_info.is_null() || (nullable && (nulls[i / 8] & (1 << (i % 8))) != 0)) {
-                values.push(column.type_info.get_value(buf));
+                values.push(column.type_info.get_value(buf)?);
             } else {
                 values.push(None);
             }
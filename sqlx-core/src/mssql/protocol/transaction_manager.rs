@@ -0,0 +1,124 @@
+use crate::io::Encode;
+use crate::mssql::io::MssqlBufMutExt;
+use crate::mssql::protocol::header::{AllHeaders, Header};
+use crate::mssql::MssqlIsolationLevel;
+
+/// A request to begin, commit, roll back or save the transaction of the connection, which the
+/// server answers with an `ENVCHANGE` for the new (or ended) transaction descriptor.
+#[derive(Debug)]
+pub(crate) struct TransactionManagerRequest<'a> {
+    pub(crate) transaction_descriptor: u64,
+    pub(crate) request: Request<'a>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Request<'a> {
+    // TM_BEGIN_XACT
+    Begin {
+        isolation_level: Option<MssqlIsolationLevel>,
+        name: &'a str,
+    },
+
+    // TM_COMMIT_XACT
+    Commit {
+        name: &'a str,
+    },
+
+    // TM_ROLLBACK_XACT; given the name of a savepoint, rolls back to that savepoint
+    Rollback {
+        name: &'a str,
+    },
+
+    // TM_SAVE_XACT
+    Save {
+        name: &'a str,
+    },
+}
+
+impl Encode<'_> for TransactionManagerRequest<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        AllHeaders(&[Header::TransactionDescriptor {
+            outstanding_request_count: 1,
+            transaction_descriptor: self.transaction_descriptor,
+        }])
+        .encode(buf);
+
+        match self.request {
+            Request::Begin {
+                isolation_level,
+                name,
+            } => {
+                buf.extend(&5_u16.to_le_bytes());
+
+                // [IsoLevel] 0 keeps the isolation level of the session
+                buf.push(match isolation_level {
+                    None => 0x00,
+                    Some(MssqlIsolationLevel::ReadUncommitted) => 0x01,
+                    Some(MssqlIsolationLevel::ReadCommitted) => 0x02,
+                    Some(MssqlIsolationLevel::RepeatableRead) => 0x03,
+                    Some(MssqlIsolationLevel::Serializable) => 0x04,
+                    Some(MssqlIsolationLevel::Snapshot) => 0x05,
+                });
+
+                buf.put_b_varchar(name);
+            }
+
+            Request::Commit { name } => {
+                buf.extend(&7_u16.to_le_bytes());
+                buf.put_b_varchar(name);
+
+                // [fBeginXact] do not begin a new transaction once this one ends
+                buf.push(0);
+            }
+
+            Request::Rollback { name } => {
+                buf.extend(&8_u16.to_le_bytes());
+                buf.put_b_varchar(name);
+
+                // [fBeginXact]
+                buf.push(0);
+            }
+
+            Request::Save { name } => {
+                buf.extend(&9_u16.to_le_bytes());
+                buf.put_b_varchar(name);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_encode_transaction_manager_request() {
+    let mut buf = Vec::new();
+
+    TransactionManagerRequest {
+        transaction_descriptor: 0,
+        request: Request::Begin {
+            isolation_level: Some(MssqlIsolationLevel::Snapshot),
+            name: "",
+        },
+    }
+    .encode(&mut buf);
+
+    // ALL_HEADERS, with the transaction descriptor
+    assert_eq!(&buf[..4], &[0x16, 0x00, 0x00, 0x00]);
+
+    // [RequestType] TM_BEGIN_XACT, [IsoLevel] SNAPSHOT, [Name]
+    assert_eq!(&buf[22..], &[0x05, 0x00, 0x05, 0x00]);
+
+    let mut buf = Vec::new();
+
+    TransactionManagerRequest {
+        transaction_descriptor: 0x0102_0304_0506_0708,
+        request: Request::Rollback { name: "sp" },
+    }
+    .encode(&mut buf);
+
+    assert_eq!(&buf[10..18], &0x0102_0304_0506_0708_u64.to_le_bytes());
+
+    // [RequestType] TM_ROLLBACK_XACT, [Name], [fBeginXact]
+    assert_eq!(
+        &buf[22..],
+        &[0x08, 0x00, 0x02, b's', 0x00, b'p', 0x00, 0x00]
+    );
+}
@@ -1,11 +1,22 @@
 use bitflags::bitflags;
-use bytes::{Buf, Bytes};
+use bytes::{Buf, Bytes, BytesMut};
 use encoding_rs::Encoding;
 
 use crate::encode::{Encode, IsNull};
 use crate::error::Error;
+use crate::mssql::io::MssqlBufExt;
 use crate::mssql::Mssql;
 
+// the length of a variable-length type that is sent as partially length-prefixed
+// bytes (PLP), such as `nvarchar(max)`
+pub(crate) const MAX_LEN: u32 = 0xFFFF;
+
+// the largest length of a variable-length type that is not sent as PLP
+pub(crate) const MAX_SHORT_LEN: u32 = 8000;
+
+// the total length of a PLP value that is NULL
+const PLP_NULL: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+
 bitflags! {
     #[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
     pub(crate) struct CollationFlags: u8 {
@@ -15,6 +26,7 @@ bitflags! {
         const IGNORE_KANA = (1 << 3);
         const BINARY = (1 << 4);
         const BINARY2 = (1 << 5);
+        const UTF8 = (1 << 6);
     }
 }
 
@@ -108,13 +120,115 @@ impl TypeInfo {
         }
     }
 
+    // builds the type from the columns that describe a type in the results of system stored
+    // procedures such as `sp_describe_first_result_set`; these use the IDs of `sys.types`
+    pub(crate) fn from_system_type(
+        id: i32,
+        max_length: i16,
+        precision: u8,
+        scale: u8,
+    ) -> Option<Self> {
+        // a max length of -1 is used for the `(max)` variants
+        let max_length = if max_length == -1 {
+            MAX_LEN
+        } else {
+            max_length as u32
+        };
+
+        Some(match id {
+            34 => Self::new(DataType::Image, max_length),
+            35 => Self::new(DataType::Text, max_length),
+            36 => Self::new(DataType::Guid, 16),
+            40 => Self::new(DataType::DateN, 3),
+            48 => Self::new(DataType::IntN, 1),
+            52 => Self::new(DataType::IntN, 2),
+            56 => Self::new(DataType::IntN, 4),
+            58 => Self::new(DataType::DateTimeN, 4),
+            59 => Self::new(DataType::FloatN, 4),
+            60 => Self::new(DataType::MoneyN, 8),
+            61 => Self::new(DataType::DateTimeN, 8),
+            62 => Self::new(DataType::FloatN, 8),
+            98 => Self::new(DataType::Variant, max_length),
+            99 => Self::new(DataType::NText, max_length),
+            104 => Self::new(DataType::BitN, 1),
+            122 => Self::new(DataType::MoneyN, 4),
+            127 => Self::new(DataType::IntN, 8),
+            165 => Self::new(DataType::BigVarBinary, max_length),
+            167 => Self::new(DataType::BigVarChar, max_length),
+            173 => Self::new(DataType::BigBinary, max_length),
+            175 => Self::new(DataType::BigChar, max_length),
+
+            // rowversion (timestamp)
+            189 => Self::new(DataType::BigBinary, 8),
+
+            231 => Self::new(DataType::NVarChar, max_length),
+            239 => Self::new(DataType::NChar, max_length),
+            241 => Self::new(DataType::Xml, MAX_LEN),
+
+            // CLR types, such as geometry and hierarchyid
+            240 => Self::new(DataType::UserDefined, max_length),
+
+            41..=43 => {
+                let ty = match id {
+                    41 => DataType::TimeN,
+                    42 => DataType::DateTime2N,
+                    _ => DataType::DateTimeOffsetN,
+                };
+
+                Self {
+                    ty,
+                    size: time_size(ty, scale)?,
+                    scale,
+                    precision: 0,
+                    collation: None,
+                }
+            }
+
+            106 | 108 => Self {
+                ty: if id == 106 {
+                    DataType::DecimalN
+                } else {
+                    DataType::NumericN
+                },
+                size: match precision {
+                    1..=9 => 5,
+                    10..=19 => 9,
+                    20..=28 => 13,
+                    _ => 17,
+                },
+                scale,
+                precision,
+                collation: None,
+            },
+
+            _ => {
+                return None;
+            }
+        })
+    }
+
     pub(crate) fn encoding(&self) -> Result<&'static Encoding, Error> {
         match self.ty {
-            DataType::NChar | DataType::NVarChar => Ok(encoding_rs::UTF_16LE),
+            DataType::NChar | DataType::NVarChar | DataType::NText | DataType::Xml => {
+                Ok(encoding_rs::UTF_16LE)
+            }
+
+            DataType::VarChar
+            | DataType::Char
+            | DataType::BigChar
+            | DataType::BigVarChar
+            | DataType::Text => {
+                let collation = self
+                    .collation
+                    .ok_or_else(|| err_protocol!("missing collation for {:?}", self.ty))?;
+
+                // a UTF-8 collation (`_UTF8`) is only ever sent to clients that acknowledged
+                // UTF8_SUPPORT in LOGIN7; the data is then UTF-8, whatever the locale
+                if collation.flags.contains(CollationFlags::UTF8) {
+                    return Ok(encoding_rs::UTF_8);
+                }
 
-            DataType::VarChar | DataType::Char | DataType::BigChar | DataType::BigVarChar => {
-                // unwrap: impossible to unwrap here, collation will be set
-                Ok(match self.collation.unwrap().locale {
+                Ok(match collation.locale {
                     // This is the Western encoding for Windows. It is an extension of ISO-8859-1,
                     // which is known as Latin 1.
                     0x0409 => encoding_rs::WINDOWS_1252,
@@ -155,29 +269,9 @@ impl TypeInfo {
             DataType::DateN => Self::new(ty, 3),
 
             DataType::TimeN | DataType::DateTime2N | DataType::DateTimeOffsetN => {
-                let scale = buf.get_u8();
-
-                let mut size = match scale {
-                    0 | 1 | 2 => 3,
-                    3 | 4 => 4,
-                    5 | 6 | 7 => 5,
-
-                    scale => {
-                        return Err(err_protocol!("invalid scale {} for type {:?}", scale, ty));
-                    }
-                };
-
-                match ty {
-                    DataType::DateTime2N => {
-                        size += 3;
-                    }
-
-                    DataType::DateTimeOffsetN => {
-                        size += 5;
-                    }
-
-                    _ => {}
-                }
+                let scale = buf.try_get_u8()?;
+                let size = time_size(ty, scale)
+                    .ok_or_else(|| err_protocol!("invalid scale {} for type {:?}", scale, ty))?;
 
                 Self {
                     scale,
@@ -193,16 +287,31 @@ impl TypeInfo {
             | DataType::BitN
             | DataType::FloatN
             | DataType::MoneyN
-            | DataType::DateTimeN
-            | DataType::Char
-            | DataType::VarChar
-            | DataType::Binary
-            | DataType::VarBinary => Self::new(ty, buf.get_u8() as u32),
+            | DataType::DateTimeN => {
+                let size = buf.try_get_u8()? as u32;
+
+                let valid = match ty {
+                    DataType::Guid => size == 16,
+                    DataType::IntN => matches!(size, 1 | 2 | 4 | 8),
+                    DataType::BitN => size == 1,
+                    _ => matches!(size, 4 | 8),
+                };
+
+                if !valid {
+                    return Err(err_protocol!("invalid size {} for type {:?}", size, ty));
+                }
+
+                Self::new(ty, size)
+            }
+
+            DataType::Char | DataType::VarChar | DataType::Binary | DataType::VarBinary => {
+                Self::new(ty, buf.try_get_u8()? as u32)
+            }
 
             DataType::Decimal | DataType::Numeric | DataType::DecimalN | DataType::NumericN => {
-                let size = buf.get_u8() as u32;
-                let precision = buf.get_u8();
-                let scale = buf.get_u8();
+                let size = buf.try_get_u8()? as u32;
+                let precision = buf.try_get_u8()?;
+                let scale = buf.try_get_u8()?;
 
                 Self {
                     size,
@@ -213,11 +322,13 @@ impl TypeInfo {
                 }
             }
 
-            DataType::BigVarBinary | DataType::BigBinary => Self::new(ty, buf.get_u16_le() as u32),
+            DataType::BigVarBinary | DataType::BigBinary => {
+                Self::new(ty, buf.try_get_u16_le()? as u32)
+            }
 
             DataType::BigVarChar | DataType::BigChar | DataType::NVarChar | DataType::NChar => {
-                let size = buf.get_u16_le() as u32;
-                let collation = Collation::get(buf);
+                let size = buf.try_get_u16_le()? as u32;
+                let collation = Collation::get(buf)?;
 
                 Self {
                     ty,
@@ -228,12 +339,60 @@ impl TypeInfo {
                 }
             }
 
-            _ => {
-                return Err(err_protocol!("unsupported data type {:?}", ty));
+            DataType::Xml => {
+                // the schema an XML value is bound to, if any, is not needed to read the value
+                if buf.try_get_u8()? == 1 {
+                    let _database = buf.get_b_varchar()?;
+                    let _owning_schema = buf.get_b_varchar()?;
+                    let _collection = buf.get_us_varchar()?;
+                }
+
+                Self::new(ty, MAX_LEN)
+            }
+
+            DataType::UserDefined => {
+                let size = buf.try_get_u16_le()? as u32;
+
+                // the names of the database, schema, type and the CLR assembly of a UDT
+                let _database = buf.get_b_varchar()?;
+                let _schema = buf.get_b_varchar()?;
+                let _type_name = buf.get_b_varchar()?;
+                let _assembly = buf.get_us_varchar()?;
+
+                Self::new(ty, size)
+            }
+
+            DataType::Text | DataType::NText => {
+                let size = buf.try_get_u32_le()?;
+                let collation = Collation::get(buf)?;
+
+                Self {
+                    ty,
+                    size,
+                    collation: Some(collation),
+                    scale: 0,
+                    precision: 0,
+                }
             }
+
+            DataType::Image | DataType::Variant => Self::new(ty, buf.try_get_u32_le()?),
         })
     }
 
+    // whether values of this type are sent as partially length-prefixed bytes (PLP); this is the
+    // case for the `(max)` variants of the variable-length types, XML and CLR UDTs
+    pub(crate) fn is_plp(&self) -> bool {
+        match self.ty {
+            DataType::BigVarBinary | DataType::BigVarChar | DataType::NVarChar => {
+                self.size == MAX_LEN
+            }
+
+            DataType::Xml | DataType::UserDefined => true,
+
+            _ => false,
+        }
+    }
+
     // writes a TYPE_INFO to the buffer
     pub(crate) fn put(&self, buf: &mut Vec<u8>) {
         buf.push(self.ty as u8);
@@ -284,18 +443,37 @@ impl TypeInfo {
 
             DataType::BigVarChar | DataType::BigChar | DataType::NVarChar | DataType::NChar => {
                 buf.extend(&(self.size as u16).to_le_bytes());
+                self.put_collation(buf);
+            }
 
-                if let Some(collation) = &self.collation {
-                    collation.put(buf);
-                } else {
-                    buf.extend(&0_u32.to_le_bytes());
-                    buf.push(0);
-                }
+            DataType::Xml => {
+                // no schema
+                buf.push(0);
             }
 
-            _ => {
-                unimplemented!("unsupported data type {:?}", self.ty);
+            DataType::UserDefined => {
+                // a UDT parameter is named by its database, schema and type; a value of this
+                // type would be rejected by the server as we don't keep the names around
+                buf.extend(&[0, 0, 0]);
+            }
+
+            DataType::Text | DataType::NText => {
+                buf.extend(&self.size.to_le_bytes());
+                self.put_collation(buf);
             }
+
+            DataType::Image | DataType::Variant => {
+                buf.extend(&self.size.to_le_bytes());
+            }
+        }
+    }
+
+    fn put_collation(&self, buf: &mut Vec<u8>) {
+        if let Some(collation) = &self.collation {
+            collation.put(buf);
+        } else {
+            buf.extend(&0_u32.to_le_bytes());
+            buf.push(0);
         }
     }
 
@@ -303,7 +481,12 @@ impl TypeInfo {
         matches!(self.ty, DataType::Null)
     }
 
-    pub(crate) fn get_value(&self, buf: &mut Bytes) -> Option<Bytes> {
+    // reads a value of this type from the buffer
+    pub(crate) fn get_value(&self, buf: &mut Bytes) -> Result<Option<Bytes>, Error> {
+        if self.is_plp() {
+            return self.get_plp_value(buf);
+        }
+
         match self.ty {
             DataType::Null
             | DataType::TinyInt
@@ -316,7 +499,7 @@ impl TypeInfo {
             | DataType::DateTime
             | DataType::Float
             | DataType::SmallMoney
-            | DataType::BigInt => Some(buf.split_to(self.size as usize)),
+            | DataType::BigInt => self.take(buf, self.size as usize).map(Some),
 
             DataType::Guid
             | DataType::IntN
@@ -332,22 +515,22 @@ impl TypeInfo {
             | DataType::TimeN
             | DataType::DateTime2N
             | DataType::DateTimeOffsetN => {
-                let size = buf.get_u8();
+                let size = self.take(buf, 1)?.get_u8();
 
                 if size == 0 || size == 0xFF {
-                    None
+                    Ok(None)
                 } else {
-                    Some(buf.split_to(size as usize))
+                    self.take(buf, size as usize).map(Some)
                 }
             }
 
             DataType::Char | DataType::VarChar | DataType::Binary | DataType::VarBinary => {
-                let size = buf.get_u8();
+                let size = self.take(buf, 1)?.get_u8();
 
                 if size == 0xFF {
-                    None
+                    Ok(None)
                 } else {
-                    Some(buf.split_to(size as usize))
+                    self.take(buf, size as usize).map(Some)
                 }
             }
 
@@ -359,28 +542,86 @@ impl TypeInfo {
             | DataType::NChar
             | DataType::Xml
             | DataType::UserDefined => {
-                let size = buf.get_u16_le();
+                let size = self.take(buf, 2)?.get_u16_le();
 
                 if size == 0xFF_FF {
-                    None
+                    Ok(None)
                 } else {
-                    Some(buf.split_to(size as usize))
+                    self.take(buf, size as usize).map(Some)
                 }
             }
 
-            DataType::Text | DataType::Image | DataType::NText | DataType::Variant => {
-                let size = buf.get_u32_le();
+            DataType::Text | DataType::Image | DataType::NText => {
+                // a text pointer and a timestamp precede the value, neither are of any use to us
+                let text_ptr_len = self.take(buf, 1)?.get_u8();
+
+                if text_ptr_len == 0 {
+                    return Ok(None);
+                }
+
+                self.take(buf, text_ptr_len as usize + 8)?;
+
+                let size = self.take(buf, 4)?.get_u32_le();
 
-                if size == 0xFFFF_FFFF {
-                    None
+                self.take(buf, size as usize).map(Some)
+            }
+
+            DataType::Variant => {
+                // the value is prefixed with its base type and the properties of that type
+                let size = self.take(buf, 4)?.get_u32_le();
+
+                if size == 0 {
+                    Ok(None)
                 } else {
-                    Some(buf.split_to(size as usize))
+                    self.take(buf, size as usize).map(Some)
                 }
             }
         }
     }
 
+    // PLP_BODY is the total length of the value followed by a list of chunks, each prefixed
+    // with its own length, that ends with an empty chunk
+    fn get_plp_value(&self, buf: &mut Bytes) -> Result<Option<Bytes>, Error> {
+        let size = self.take(buf, 8)?.get_u64_le();
+
+        if size == PLP_NULL {
+            return Ok(None);
+        }
+
+        // NOTE: the total length is 0xFFFF_FFFF_FFFF_FFFE if the server did not know it upfront
+        let mut value = BytesMut::with_capacity((size as usize).min(buf.len()));
+
+        loop {
+            let chunk_size = self.take(buf, 4)?.get_u32_le();
+
+            if chunk_size == 0 {
+                break;
+            }
+
+            value.extend_from_slice(&self.take(buf, chunk_size as usize)?);
+        }
+
+        Ok(Some(value.freeze()))
+    }
+
+    fn take(&self, buf: &mut Bytes, size: usize) -> Result<Bytes, Error> {
+        if buf.len() < size {
+            return Err(err_protocol!(
+                "expected {} more bytes for a value of {:?} but only {} remain",
+                size,
+                self.ty,
+                buf.len()
+            ));
+        }
+
+        Ok(buf.split_to(size))
+    }
+
     pub(crate) fn put_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+        if self.is_plp() {
+            return self.put_plp_value(buf, value);
+        }
+
         match self.ty {
             DataType::Null
             | DataType::TinyInt
@@ -435,6 +676,28 @@ impl TypeInfo {
         }
     }
 
+    // writes the value as a PLP_BODY with a single chunk
+    pub(crate) fn put_plp_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
+        let offset = buf.len();
+        buf.extend(&0_u64.to_le_bytes());
+        buf.extend(&0_u32.to_le_bytes());
+
+        if let IsNull::Yes = value.encode(buf) {
+            buf.truncate(offset);
+            buf.extend(&PLP_NULL.to_le_bytes());
+
+            return;
+        }
+
+        let size = buf.len() - offset - 12;
+
+        buf[offset..(offset + 8)].copy_from_slice(&(size as u64).to_le_bytes());
+        buf[(offset + 8)..(offset + 12)].copy_from_slice(&(size as u32).to_le_bytes());
+
+        // terminator
+        buf.extend(&0_u32.to_le_bytes());
+    }
+
     pub(crate) fn put_fixed_value<'q, T: Encode<'q, Mssql>>(&self, buf: &mut Vec<u8>, value: T) {
         let _ = value.encode(buf);
     }
@@ -498,16 +761,43 @@ impl TypeInfo {
                 4 => "int",
                 8 => "bigint",
 
-                _ => unreachable!("invalid size {} for int"),
+                _ => unreachable!("invalid size {} for int", self.size),
             }),
 
             DataType::FloatN => s.push_str(match self.size {
                 4 => "real",
                 8 => "float",
 
-                _ => unreachable!("invalid size {} for float"),
+                _ => unreachable!("invalid size {} for float", self.size),
+            }),
+
+            DataType::Money => s.push_str("money"),
+            DataType::SmallMoney => s.push_str("smallmoney"),
+
+            DataType::MoneyN => s.push_str(match self.size {
+                4 => "smallmoney",
+                8 => "money",
+
+                _ => unreachable!("invalid size {} for money", self.size),
             }),
 
+            DataType::Decimal | DataType::Numeric | DataType::DecimalN | DataType::NumericN => {
+                // name
+                s.push_str(match self.ty {
+                    DataType::Decimal | DataType::DecimalN => "decimal",
+                    DataType::Numeric | DataType::NumericN => "numeric",
+
+                    _ => unreachable!(),
+                });
+
+                // precision and scale
+                s.push('(');
+                let _ = itoa::fmt(&mut *s, self.precision);
+                s.push(',');
+                let _ = itoa::fmt(&mut *s, self.scale);
+                s.push(')');
+            }
+
             DataType::VarChar
             | DataType::NVarChar
             | DataType::BigVarChar
@@ -516,34 +806,95 @@ impl TypeInfo {
             | DataType::NChar => {
                 // name
                 s.push_str(match self.ty {
-                    DataType::VarChar => "varchar",
+                    DataType::VarChar | DataType::BigVarChar => "varchar",
                     DataType::NVarChar => "nvarchar",
-                    DataType::BigVarChar => "bigvarchar",
-                    DataType::Char => "char",
-                    DataType::BigChar => "bigchar",
+                    DataType::Char | DataType::BigChar => "char",
                     DataType::NChar => "nchar",
 
                     _ => unreachable!(),
                 });
 
-                // size
-                if self.size < 8000 && self.size > 0 {
+                // size; in bytes, which is twice the length in characters for the N types
+                if self.size <= MAX_SHORT_LEN && self.size > 0 {
+                    let len = match self.ty {
+                        DataType::NVarChar | DataType::NChar => self.size / 2,
+                        _ => self.size,
+                    };
+
                     s.push_str("(");
-                    let _ = itoa::fmt(&mut *s, self.size);
+                    let _ = itoa::fmt(&mut *s, len);
                     s.push_str(")");
                 } else {
                     s.push_str("(max)");
                 }
             }
 
-            _ => unimplemented!("fmt: unsupported data type {:?}", self.ty),
+            DataType::Bit | DataType::BitN => s.push_str("bit"),
+            DataType::Guid => s.push_str("uniqueidentifier"),
+            DataType::DateN => s.push_str("date"),
+            DataType::DateTime => s.push_str("datetime"),
+            DataType::SmallDateTime => s.push_str("smalldatetime"),
+
+            DataType::DateTimeN => s.push_str(match self.size {
+                4 => "smalldatetime",
+                8 => "datetime",
+
+                _ => unreachable!("invalid size {} for datetime", self.size),
+            }),
+
+            DataType::TimeN | DataType::DateTime2N | DataType::DateTimeOffsetN => {
+                // name
+                s.push_str(match self.ty {
+                    DataType::TimeN => "time",
+                    DataType::DateTime2N => "datetime2",
+                    DataType::DateTimeOffsetN => "datetimeoffset",
+
+                    _ => unreachable!(),
+                });
+
+                // scale
+                s.push('(');
+                let _ = itoa::fmt(&mut *s, self.scale);
+                s.push(')');
+            }
+
+            DataType::Binary
+            | DataType::VarBinary
+            | DataType::BigBinary
+            | DataType::BigVarBinary => {
+                // name
+                s.push_str(match self.ty {
+                    DataType::Binary | DataType::BigBinary => "binary",
+                    DataType::VarBinary | DataType::BigVarBinary => "varbinary",
+
+                    _ => unreachable!(),
+                });
+
+                // size
+                if self.size <= MAX_SHORT_LEN && self.size > 0 {
+                    s.push('(');
+                    let _ = itoa::fmt(&mut *s, self.size);
+                    s.push(')');
+                } else {
+                    s.push_str("(max)");
+                }
+            }
+
+            DataType::Xml => s.push_str("xml"),
+            DataType::Text => s.push_str("text"),
+            DataType::NText => s.push_str("ntext"),
+            DataType::Image => s.push_str("image"),
+            DataType::Variant => s.push_str("sql_variant"),
+
+            // the name of a CLR user-defined type is not kept around
+            DataType::UserDefined => s.push_str("udt"),
         }
     }
 }
 
 impl DataType {
     pub(crate) fn get(buf: &mut Bytes) -> Result<Self, Error> {
-        Ok(match buf.get_u8() {
+        Ok(match buf.try_get_u8()? {
             0x1f => DataType::Null,
             0x30 => DataType::TinyInt,
             0x32 => DataType::Bit,
@@ -587,6 +938,11 @@ impl DataType {
             0x63 => DataType::NText,
             0x62 => DataType::Variant,
 
+            // TVP_TYPE_INFO is only ever sent by a client
+            0xf3 => {
+                return Err(err_protocol!("table-valued parameters are not supported"));
+            }
+
             ty => {
                 return Err(err_protocol!("unknown data type 0x{:02x}", ty));
             }
@@ -595,19 +951,19 @@ impl DataType {
 }
 
 impl Collation {
-    pub(crate) fn get(buf: &mut Bytes) -> Collation {
-        let locale_sort_version = buf.get_u32_le();
+    pub(crate) fn get(buf: &mut Bytes) -> Result<Collation, Error> {
+        let locale_sort_version = buf.try_get_u32_le()?;
         let locale = locale_sort_version & 0xfffff;
         let flags = CollationFlags::from_bits_truncate(((locale_sort_version >> 20) & 0xFF) as u8);
         let version = (locale_sort_version >> 28) as u8;
-        let sort = buf.get_u8();
+        let sort = buf.try_get_u8()?;
 
-        Collation {
+        Ok(Collation {
             locale,
             flags,
             sort,
             version,
-        }
+        })
     }
 
     pub(crate) fn put(&self, buf: &mut Vec<u8>) {
@@ -618,3 +974,97 @@ impl Collation {
         buf.push(self.sort);
     }
 }
+
+// the length of a time, datetime2 or datetimeoffset value with the given fractional-second scale
+fn time_size(ty: DataType, scale: u8) -> Option<u32> {
+    let time = match scale {
+        0 | 1 | 2 => 3,
+        3 | 4 => 4,
+        5 | 6 | 7 => 5,
+
+        _ => {
+            return None;
+        }
+    };
+
+    Some(match ty {
+        DataType::DateTime2N => time + 3,
+        DataType::DateTimeOffsetN => time + 5,
+
+        _ => time,
+    })
+}
+
+#[test]
+fn test_get_plp_value() {
+    let ty = TypeInfo::new(DataType::BigVarBinary, MAX_LEN);
+
+    #[rustfmt::skip]
+    let mut buf = Bytes::from_static(&[
+        5, 0, 0, 0, 0, 0, 0, 0,
+        2, 0, 0, 0, 1, 2,
+        3, 0, 0, 0, 3, 4, 5,
+        0, 0, 0, 0,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    ]);
+
+    let value = ty.get_value(&mut buf).unwrap();
+
+    assert_eq!(value.as_deref(), Some(&[1, 2, 3, 4, 5][..]));
+    assert_eq!(ty.get_value(&mut buf).unwrap(), None);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_put_plp_value() {
+    let ty = TypeInfo::new(DataType::BigVarBinary, MAX_LEN);
+    let mut buf = Vec::new();
+
+    ty.put_value(&mut buf, &[1_u8, 2, 3][..]);
+
+    let value = ty.get_value(&mut Bytes::from(buf)).unwrap();
+
+    assert_eq!(value.as_deref(), Some(&[1, 2, 3][..]));
+}
+
+#[test]
+fn test_get_value_out_of_bounds() {
+    let ty = TypeInfo::new(DataType::NVarChar, 20);
+    let mut buf = Bytes::from_static(&[20, 0, b'a', 0]);
+
+    assert!(ty.get_value(&mut buf).is_err());
+}
+
+#[test]
+fn test_get_type_info_out_of_bounds() {
+    // NVARCHAR(10) and its collation, cut short at every byte
+    let full = [0xe7, 0x14, 0x00, 0x09, 0x04, 0xd0, 0x00, 0x34];
+
+    assert!(TypeInfo::get(&mut Bytes::copy_from_slice(&full)).is_ok());
+
+    for len in 0..full.len() {
+        assert!(TypeInfo::get(&mut Bytes::copy_from_slice(&full[..len])).is_err());
+    }
+
+    // XML bound to a schema, with the names of the schema missing
+    assert!(TypeInfo::get(&mut Bytes::from_static(&[0xf1, 0x01, 0x05])).is_err());
+}
+
+#[test]
+fn test_fmt_string_types() {
+    fn name(ty: DataType, size: u32) -> String {
+        let mut s = String::new();
+        TypeInfo::new(ty, size).fmt(&mut s);
+        s
+    }
+
+    assert_eq!(name(DataType::BigVarChar, 8000), "varchar(8000)");
+    assert_eq!(name(DataType::BigVarChar, MAX_LEN), "varchar(max)");
+
+    // the size of the N types is in bytes
+    assert_eq!(name(DataType::NVarChar, 2), "nvarchar(1)");
+    assert_eq!(name(DataType::NVarChar, 6000), "nvarchar(3000)");
+    assert_eq!(name(DataType::NVarChar, 8000), "nvarchar(4000)");
+    assert_eq!(name(DataType::NChar, 20), "nchar(10)");
+    assert_eq!(name(DataType::NVarChar, MAX_LEN), "nvarchar(max)");
+}
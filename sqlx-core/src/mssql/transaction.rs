@@ -1,39 +1,99 @@
-use std::borrow::Cow;
-
 use futures_core::future::BoxFuture;
 
 use crate::error::Error;
-use crate::executor::Executor;
 use crate::mssql::protocol::packet::PacketType;
 use crate::mssql::protocol::sql_batch::SqlBatch;
+use crate::mssql::protocol::transaction_manager::{Request, TransactionManagerRequest};
 use crate::mssql::{Mssql, MssqlConnection};
-use crate::transaction::TransactionManager;
+use crate::transaction::{Transaction, TransactionManager};
+
+/// The isolation level of a transaction, which controls how the transaction is isolated from
+/// the changes made by other transactions.
+///
+/// It is used by the [`begin_with_isolation_level`](MssqlConnection::begin_with_isolation_level)
+/// method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MssqlIsolationLevel {
+    /// Statements can read rows that have been modified by other transactions but not
+    /// yet committed.
+    ReadUncommitted,
+
+    /// Statements cannot read data that has been modified but not committed by other
+    /// transactions.
+    ///
+    /// This is the default for SQL Server.
+    ReadCommitted,
+
+    /// Statements cannot read data that has been modified but not committed by other
+    /// transactions, and no other transaction can modify the data that has been read by the
+    /// current transaction until it ends.
+    RepeatableRead,
+
+    /// As [`RepeatableRead`](Self::RepeatableRead), and other transactions cannot insert new
+    /// rows that would be read by the statements of the current transaction.
+    Serializable,
+
+    /// Statements read the data as it was at the start of the transaction, without locking it.
+    ///
+    /// The `ALLOW_SNAPSHOT_ISOLATION` option must be set on the database.
+    Snapshot,
+}
+
+impl MssqlConnection {
+    /// Begins a new transaction with the given isolation level.
+    ///
+    /// Once the transaction has ended, the connection goes back to `READ COMMITTED`, the
+    /// default for SQL Server.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::mssql::{MssqlConnection, MssqlIsolationLevel};
+    /// # async fn example(conn: &mut MssqlConnection) -> Result<(), sqlx_core::error::Error> {
+    /// let tx = conn
+    ///     .begin_with_isolation_level(MssqlIsolationLevel::Snapshot)
+    ///     .await?;
+    ///
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn begin_with_isolation_level(
+        &mut self,
+        isolation_level: MssqlIsolationLevel,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Mssql>, Error>> {
+        Transaction::begin_with(self, move |conn, depth| {
+            begin(conn, depth, Some(isolation_level))
+        })
+    }
+}
 
 /// Implementation of [`TransactionManager`] for MSSQL.
+///
+/// Transactions are started and ended with Transaction Manager Requests, rather than with
+/// `BEGIN TRAN` and `COMMIT TRAN` in a SQL batch, so that the transaction descriptor the server
+/// answers with is the one source of truth for whether a transaction is active.
 pub struct MssqlTransactionManager;
 
 impl TransactionManager for MssqlTransactionManager {
     type Database = Mssql;
 
     fn begin(conn: &mut MssqlConnection, depth: usize) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move {
-            let query = if depth == 0 {
-                Cow::Borrowed("BEGIN TRAN ")
-            } else {
-                Cow::Owned(format!("SAVE TRAN _sqlx_savepoint_{}", depth))
-            };
-
-            conn.execute(&*query).await?;
-
-            Ok(())
-        })
+        begin(conn, depth, None)
     }
 
     fn commit(conn: &mut MssqlConnection, depth: usize) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
+            // the server rolls back the whole transaction on some errors (and on any error with
+            // XACT_ABORT set), after which there is nothing left to commit at any depth
+            if conn.stream.transaction_descriptor == 0 {
+                return Err(Error::TransactionRolledBack);
+            }
+
             if depth == 1 {
                 // savepoints are not released in MSSQL
-                conn.execute("COMMIT TRAN").await?;
+                send_request(conn, Request::Commit { name: "" }).await?;
+                reset_isolation_level(conn).await?;
             }
 
             Ok(())
@@ -42,32 +102,118 @@ impl TransactionManager for MssqlTransactionManager {
 
     fn rollback(conn: &mut MssqlConnection, depth: usize) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
-            let query = if depth == 1 {
-                Cow::Borrowed("ROLLBACK TRAN")
-            } else {
-                Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
-            };
+            // when the server has already rolled back the transaction, everything that was
+            // asked to be undone has been undone
+            if conn.stream.transaction_descriptor != 0 {
+                let savepoint = savepoint_name(depth - 1);
+
+                send_request(conn, Request::Rollback { name: &savepoint }).await?;
+            }
 
-            conn.execute(&*query).await?;
+            if depth == 1 {
+                reset_isolation_level(conn).await?;
+            }
 
             Ok(())
         })
     }
 
     fn start_rollback(conn: &mut MssqlConnection, depth: usize) {
-        let query = if depth == 1 {
-            Cow::Borrowed("ROLLBACK TRAN")
+        if conn.stream.transaction_descriptor != 0 {
+            conn.stream.pending_done_count += 1;
+            conn.stream.write_packet(
+                PacketType::TransactionManagerRequest,
+                TransactionManagerRequest {
+                    transaction_descriptor: conn.stream.transaction_descriptor,
+                    request: Request::Rollback {
+                        name: &savepoint_name(depth - 1),
+                    },
+                },
+            );
+        }
+
+        if depth == 1 {
+            write_reset_isolation_level(conn);
+        }
+    }
+}
+
+// an isolation level of `None` keeps that of the session
+fn begin(
+    conn: &mut MssqlConnection,
+    depth: usize,
+    isolation_level: Option<MssqlIsolationLevel>,
+) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let savepoint = savepoint_name(depth);
+
+        let request = if depth == 0 {
+            Request::Begin {
+                isolation_level,
+                name: "",
+            }
         } else {
-            Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
+            Request::Save { name: &savepoint }
         };
 
-        conn.stream.pending_done_count += 1;
-        conn.stream.write_packet(
-            PacketType::SqlBatch,
-            SqlBatch {
-                transaction_descriptor: conn.stream.transaction_descriptor,
-                sql: &*query,
-            },
-        );
+        send_request(conn, request).await?;
+
+        if depth == 0 {
+            conn.stream.reset_isolation_level = isolation_level.is_some();
+        }
+
+        Ok(())
+    })
+}
+
+async fn send_request(conn: &mut MssqlConnection, request: Request<'_>) -> Result<(), Error> {
+    conn.stream.wait_until_ready().await?;
+    conn.stream.pending_done_count += 1;
+
+    conn.stream.write_packet(
+        PacketType::TransactionManagerRequest,
+        TransactionManagerRequest {
+            transaction_descriptor: conn.stream.transaction_descriptor,
+            request,
+        },
+    );
+
+    // the new transaction descriptor arrives in an ENVCHANGE before the DONE
+    conn.stream.wait_until_ready().await
+}
+
+async fn reset_isolation_level(conn: &mut MssqlConnection) -> Result<(), Error> {
+    conn.stream.wait_until_ready().await?;
+    write_reset_isolation_level(conn);
+    conn.stream.wait_until_ready().await
+}
+
+// the isolation level a transaction was begun with is kept by the session after the transaction
+// has ended; put back the default once it has
+fn write_reset_isolation_level(conn: &mut MssqlConnection) {
+    if !conn.stream.reset_isolation_level {
+        return;
+    }
+
+    conn.stream.reset_isolation_level = false;
+    conn.stream.pending_done_count += 1;
+
+    conn.stream.write_packet(
+        PacketType::SqlBatch,
+        SqlBatch {
+            // the transaction has ended, even if the server has yet to tell us
+            transaction_descriptor: 0,
+            sql: "SET TRANSACTION ISOLATION LEVEL READ COMMITTED",
+        },
+    );
+}
+
+// the name of the savepoint for a transaction at the given depth; the outermost
+// transaction has no name
+fn savepoint_name(depth: usize) -> String {
+    if depth == 0 {
+        String::new()
+    } else {
+        format!("_sqlx_savepoint_{}", depth)
     }
 }
@@ -1,6 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
-use crate::mssql::protocol::type_info::TypeInfo as ProtocolTypeInfo;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo as ProtocolTypeInfo};
 use crate::type_info::TypeInfo;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,3 +17,30 @@ impl Display for MssqlTypeInfo {
         f.pad(&*buf)
     }
 }
+
+impl MssqlTypeInfo {
+    #[doc(hidden)]
+    pub fn __type_feature_gate(&self) -> Option<&'static str> {
+        match self.0.ty {
+            DataType::Decimal
+            | DataType::Numeric
+            | DataType::DecimalN
+            | DataType::NumericN
+            | DataType::Money
+            | DataType::SmallMoney
+            | DataType::MoneyN => Some("bigdecimal"),
+
+            DataType::DateN
+            | DataType::TimeN
+            | DataType::DateTime2N
+            | DataType::DateTimeOffsetN
+            | DataType::DateTime
+            | DataType::SmallDateTime
+            | DataType::DateTimeN => Some("time"),
+
+            DataType::Guid => Some("uuid"),
+
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use bigdecimal::BigDecimal;
+use byteorder::{ByteOrder, LittleEndian};
+use num_bigint::{BigInt, Sign};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+// the largest precision (and scale) of a DECIMAL in MSSQL
+const MAX_PRECISION: u8 = 38;
+
+// a sign byte followed by the 16 bytes needed to hold 38 decimal digits
+const MAX_SIZE: u32 = 17;
+
+impl Type<Mssql> for BigDecimal {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(decimal_type_info(0))
+    }
+}
+
+impl Encode<'_, Mssql> for BigDecimal {
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        let (_, scale) = to_mssql_decimal(self);
+
+        Some(MssqlTypeInfo(decimal_type_info(scale)))
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let (digits, _) = to_mssql_decimal(self);
+        let (sign, mut bytes) = digits.to_bytes_le();
+
+        // 1 for a positive value and 0 for a negative value
+        buf.push(if sign == Sign::Minus { 0 } else { 1 });
+
+        bytes.resize((MAX_SIZE - 1) as usize, 0);
+        buf.extend(&bytes);
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        MAX_SIZE as usize
+    }
+}
+
+impl Decode<'_, Mssql> for BigDecimal {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(
+            ty.0.ty,
+            DataType::Decimal
+                | DataType::Numeric
+                | DataType::DecimalN
+                | DataType::NumericN
+                | DataType::Money
+                | DataType::SmallMoney
+                | DataType::MoneyN
+        )
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let buf = value.as_bytes()?;
+
+        match value.type_info.0.ty {
+            DataType::Money | DataType::SmallMoney | DataType::MoneyN => decode_money(buf),
+
+            _ => {
+                if buf.is_empty() {
+                    return Err("invalid length 0 for DECIMAL".into());
+                }
+
+                // a sign byte followed by the unsigned, little-endian digits
+                let sign = if buf[0] == 0 { Sign::Minus } else { Sign::Plus };
+                let digits = BigInt::from_bytes_le(sign, &buf[1..]);
+
+                Ok(BigDecimal::new(digits, value.type_info.0.scale.into()))
+            }
+        }
+    }
+}
+
+// MONEY and SMALLMONEY are integers of ten-thousandths of a currency unit
+fn decode_money(buf: &[u8]) -> Result<BigDecimal, BoxDynError> {
+    let value = match buf.len() {
+        // the high 32 bits are sent before the low 32 bits
+        8 => {
+            let high = LittleEndian::read_i32(&buf[..4]) as i64;
+            let low = LittleEndian::read_u32(&buf[4..]) as i64;
+
+            (high << 32) | low
+        }
+
+        4 => LittleEndian::read_i32(buf) as i64,
+
+        len => {
+            return Err(format!("invalid length {} for MONEY", len).into());
+        }
+    };
+
+    Ok(BigDecimal::new(value.into(), 4))
+}
+
+fn decimal_type_info(scale: u8) -> TypeInfo {
+    TypeInfo {
+        ty: DataType::DecimalN,
+        size: MAX_SIZE,
+        scale,
+        precision: MAX_PRECISION,
+        collation: None,
+    }
+}
+
+// the digits and scale of the value as a `DECIMAL(38, scale)`
+//
+// a value with more than 38 digits loses as many digits of its fraction as it takes to fit,
+// and a whole part that does not fit even then is clamped to the largest DECIMAL(38, 0)
+fn to_mssql_decimal(value: &BigDecimal) -> (BigInt, u8) {
+    let (_, exponent) = value.as_bigint_and_exponent();
+
+    // a negative exponent is a whole number, any digits past the largest scale are truncated
+    let mut scale = exponent.max(0).min(MAX_PRECISION.into());
+    let (mut digits, _) = value.with_scale(scale).into_bigint_and_exponent();
+
+    let excess = count_digits(&digits) as i64 - i64::from(MAX_PRECISION);
+
+    if excess > 0 {
+        scale = (scale - excess).max(0);
+        digits = value.with_scale(scale).into_bigint_and_exponent().0;
+
+        if count_digits(&digits) > MAX_PRECISION as usize {
+            let max: BigInt = "9".repeat(MAX_PRECISION as usize).parse().unwrap();
+
+            digits = if digits.sign() == Sign::Minus {
+                -max
+            } else {
+                max
+            };
+        }
+    }
+
+    (digits, scale as u8)
+}
+
+fn count_digits(value: &BigInt) -> usize {
+    value.to_string().trim_start_matches('-').len()
+}
+
+#[test]
+fn test_to_mssql_decimal() {
+    fn to_mssql_decimal_str(value: &str) -> (String, u8) {
+        let (digits, scale) = to_mssql_decimal(&value.parse().unwrap());
+
+        (digits.to_string(), scale)
+    }
+
+    assert_eq!(to_mssql_decimal_str("12345.6789"), ("123456789".into(), 4));
+    assert_eq!(to_mssql_decimal_str("1e3"), ("1000".into(), 0));
+
+    // 40 digits, 20 of them in the fraction
+    assert_eq!(
+        to_mssql_decimal_str("12345678901234567890.12345678901234567890"),
+        ("12345678901234567890123456789012345678".into(), 18)
+    );
+
+    // a whole part of 40 digits
+    let nines = "9".repeat(38);
+
+    assert_eq!(
+        to_mssql_decimal_str(&format!("1{}0.5", "0".repeat(38))),
+        (nines.clone(), 0)
+    );
+
+    assert_eq!(
+        to_mssql_decimal_str(&format!("-1{}0.5", "0".repeat(38))),
+        (format!("-{}", nines), 0)
+    );
+}
@@ -0,0 +1,76 @@
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo, MAX_LEN, MAX_SHORT_LEN};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+impl Type<Mssql> for [u8] {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::BigVarBinary, 0))
+    }
+}
+
+impl Encode<'_, Mssql> for &'_ [u8] {
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        // an empty buffer needs to be encoded as `varbinary(1)` and a buffer that is too
+        // long for `varbinary(8000)` as `varbinary(max)`
+        let size = match self.len() as u32 {
+            0 => 1,
+            size if size > MAX_SHORT_LEN => MAX_LEN,
+            size => size,
+        };
+
+        Some(MssqlTypeInfo(TypeInfo::new(DataType::BigVarBinary, size)))
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        buf.extend_from_slice(self);
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Mssql> for &'r [u8] {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(
+            ty.0.ty,
+            DataType::BigVarBinary
+                | DataType::BigBinary
+                | DataType::VarBinary
+                | DataType::Binary
+                | DataType::Image
+                | DataType::UserDefined
+        )
+    }
+
+    fn decode(value: MssqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.as_bytes()
+    }
+}
+
+impl Type<Mssql> for Vec<u8> {
+    fn type_info() -> MssqlTypeInfo {
+        <[u8] as Type<Mssql>>::type_info()
+    }
+}
+
+impl Encode<'_, Mssql> for Vec<u8> {
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        <&[u8] as Encode<Mssql>>::produces(&self.as_slice())
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        <&[u8] as Encode<Mssql>>::encode_by_ref(&self.as_slice(), buf)
+    }
+}
+
+impl Decode<'_, Mssql> for Vec<u8> {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        <&[u8] as Decode<Mssql>>::accepts(ty)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        <&[u8] as Decode<Mssql>>::decode(value).map(ToOwned::to_owned)
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::DataType;
+use crate::mssql::types::datetime;
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+impl Type<Mssql> for NaiveDate {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::DateN)
+    }
+}
+
+impl Encode<'_, Mssql> for NaiveDate {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        datetime::encode_date(buf, days(self));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for NaiveDate {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::DateN)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        date(datetime::decode_date(&value)?)
+    }
+}
+
+impl Type<Mssql> for NaiveTime {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::TimeN)
+    }
+}
+
+impl Encode<'_, Mssql> for NaiveTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        datetime::encode_time(buf, nanos(self));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for NaiveTime {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::TimeN)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        time(datetime::decode_time(&value)?)
+    }
+}
+
+impl Type<Mssql> for NaiveDateTime {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::DateTime2N)
+    }
+}
+
+impl Encode<'_, Mssql> for NaiveDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        datetime::encode_datetime(buf, days(&self.date()), nanos(&self.time()));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for NaiveDateTime {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(
+            ty.0.ty,
+            DataType::DateTime2N
+                | DataType::DateTime
+                | DataType::SmallDateTime
+                | DataType::DateTimeN
+        )
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let (days, nanos) = datetime::decode_datetime(&value)?;
+
+        Ok(date(days)?.and_time(time(nanos)?))
+    }
+}
+
+impl Type<Mssql> for DateTime<Utc> {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::DateTimeOffsetN)
+    }
+}
+
+impl Encode<'_, Mssql> for DateTime<Utc> {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let naive = self.naive_utc();
+
+        datetime::encode_datetime_offset(buf, days(&naive.date()), nanos(&naive.time()), 0);
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for DateTime<Utc> {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::DateTimeOffsetN)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        // the date and time are sent in UTC
+        let (days, nanos, _) = datetime::decode_datetime_offset(&value)?;
+        let naive = date(days)?.and_time(time(nanos)?);
+
+        Ok(DateTime::from_utc(naive, Utc))
+    }
+}
+
+// the number of days since 0001-01-01
+fn days(date: &NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) - 1
+}
+
+// the number of nanoseconds since midnight
+fn nanos(time: &NaiveTime) -> u64 {
+    u64::from(time.num_seconds_from_midnight()) * NANOS_PER_SEC + u64::from(time.nanosecond())
+}
+
+fn date(days: i64) -> Result<NaiveDate, BoxDynError> {
+    NaiveDate::from_num_days_from_ce_opt((days + 1) as i32)
+        .ok_or_else(|| format!("date out of range: {} days since 0001-01-01", days).into())
+}
+
+fn time(nanos: u64) -> Result<NaiveTime, BoxDynError> {
+    NaiveTime::from_num_seconds_from_midnight_opt(
+        (nanos / NANOS_PER_SEC) as u32,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+    .ok_or_else(|| format!("time out of range: {} nanoseconds since midnight", nanos).into())
+}
@@ -0,0 +1,200 @@
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{MssqlTypeInfo, MssqlValueRef};
+
+// the wire formats of the date and time types, shared by the `chrono` and `time` integrations
+
+// the number of digits of a fractional second that are sent for a time, the most MSSQL supports
+const SCALE: u8 = 7;
+
+// the number of days from 0001-01-01 to 1900-01-01, the epoch of DATETIME and SMALLDATETIME
+const DAYS_TO_1900: i64 = 693_595;
+
+// the number of days from 0001-01-01 to 9999-12-31, the last day of a DATE
+const MAX_DAYS: i64 = 3_652_058;
+
+const NANOS_PER_MINUTE: u64 = 60_000_000_000;
+
+// the last tick of a day, as a TIME(7) counts in units of 100 nanoseconds
+const MAX_NANOS: u64 = 24 * 60 * NANOS_PER_MINUTE - 100;
+
+pub(super) fn type_info(ty: DataType) -> MssqlTypeInfo {
+    let (size, scale) = match ty {
+        DataType::DateN => (3, 0),
+        DataType::TimeN => (5, SCALE),
+        DataType::DateTime2N => (8, SCALE),
+        DataType::DateTimeOffsetN => (10, SCALE),
+
+        _ => unreachable!("{:?} is not a date or time type", ty),
+    };
+
+    MssqlTypeInfo(TypeInfo {
+        ty,
+        size,
+        scale,
+        precision: 0,
+        collation: None,
+    })
+}
+
+// a DATE is the number of days since 0001-01-01; a date outside of 0001-01-01 to 9999-12-31
+// is clamped to that range, as the value cannot fail to encode
+pub(super) fn encode_date(buf: &mut Vec<u8>, days: i64) {
+    let days = if days < 0 { 0 } else { days.min(MAX_DAYS) };
+
+    buf.extend(&(days as u32).to_le_bytes()[..3]);
+}
+
+// a TIME is the number of 10^-scale seconds since midnight
+pub(super) fn encode_time(buf: &mut Vec<u8>, nanos: u64) {
+    buf.extend(&(nanos / 100).to_le_bytes()[..5]);
+}
+
+// a DATETIME2 is a TIME followed by a DATE; as with a DATE, a date and time out of range
+// is clamped to the first or last moment of the range
+pub(super) fn encode_datetime(buf: &mut Vec<u8>, days: i64, nanos: u64) {
+    let nanos = if days < 0 {
+        0
+    } else if days > MAX_DAYS {
+        MAX_NANOS
+    } else {
+        nanos
+    };
+
+    encode_time(buf, nanos);
+    encode_date(buf, days);
+}
+
+// a DATETIMEOFFSET is a DATETIME2 in UTC followed by the offset in minutes
+pub(super) fn encode_datetime_offset(buf: &mut Vec<u8>, days: i64, nanos: u64, offset: i16) {
+    encode_datetime(buf, days, nanos);
+    buf.extend(&offset.to_le_bytes());
+}
+
+pub(super) fn decode_date(value: &MssqlValueRef<'_>) -> Result<i64, BoxDynError> {
+    let buf = value.as_bytes()?;
+
+    if buf.len() != 3 {
+        return Err(format!("invalid length {} for DATE", buf.len()).into());
+    }
+
+    Ok(LittleEndian::read_u24(buf).into())
+}
+
+pub(super) fn decode_time(value: &MssqlValueRef<'_>) -> Result<u64, BoxDynError> {
+    let buf = value.as_bytes()?;
+
+    if buf.is_empty() || buf.len() > 5 {
+        return Err(format!("invalid length {} for TIME", buf.len()).into());
+    }
+
+    Ok(time_from_bytes(buf, value.type_info.0.scale))
+}
+
+// the days since 0001-01-01 and the nanoseconds since midnight of a DATETIME2, DATETIME
+// or SMALLDATETIME
+pub(super) fn decode_datetime(value: &MssqlValueRef<'_>) -> Result<(i64, u64), BoxDynError> {
+    let buf = value.as_bytes()?;
+
+    match (value.type_info.0.ty, buf.len()) {
+        (DataType::DateTime2N, len) if len > 3 && len <= 8 => {
+            let (time, date) = buf.split_at(len - 3);
+
+            Ok((
+                LittleEndian::read_u24(date).into(),
+                time_from_bytes(time, value.type_info.0.scale),
+            ))
+        }
+
+        // DATETIME is the number of days since 1900-01-01 and the number of
+        // 1/300ths of a second since midnight
+        (DataType::DateTime, 8) | (DataType::DateTimeN, 8) => {
+            let days = LittleEndian::read_i32(&buf[..4]);
+            let ticks = LittleEndian::read_u32(&buf[4..]);
+
+            Ok((
+                DAYS_TO_1900 + i64::from(days),
+                u64::from(ticks) * 10_000_000 / 3,
+            ))
+        }
+
+        // SMALLDATETIME is the number of days since 1900-01-01 and the number of
+        // minutes since midnight
+        (DataType::SmallDateTime, 4) | (DataType::DateTimeN, 4) => {
+            let days = LittleEndian::read_u16(&buf[..2]);
+            let minutes = LittleEndian::read_u16(&buf[2..]);
+
+            Ok((
+                DAYS_TO_1900 + i64::from(days),
+                u64::from(minutes) * NANOS_PER_MINUTE,
+            ))
+        }
+
+        (ty, len) => Err(format!("invalid length {} for {:?}", len, ty).into()),
+    }
+}
+
+// the days since 0001-01-01 and the nanoseconds since midnight, both in UTC, and the offset
+// in minutes of a DATETIMEOFFSET
+pub(super) fn decode_datetime_offset(
+    value: &MssqlValueRef<'_>,
+) -> Result<(i64, u64, i16), BoxDynError> {
+    let buf = value.as_bytes()?;
+    let len = buf.len();
+
+    if len <= 5 || len > 10 {
+        return Err(format!("invalid length {} for DATETIMEOFFSET", len).into());
+    }
+
+    let (time, rest) = buf.split_at(len - 5);
+
+    Ok((
+        LittleEndian::read_u24(&rest[..3]).into(),
+        time_from_bytes(time, value.type_info.0.scale),
+        LittleEndian::read_i16(&rest[3..]),
+    ))
+}
+
+fn time_from_bytes(buf: &[u8], scale: u8) -> u64 {
+    let ticks = LittleEndian::read_uint(buf, buf.len());
+
+    ticks * 10_u64.pow(9 - u32::from(scale.min(SCALE)))
+}
+
+#[test]
+fn test_encode_date_out_of_range() {
+    fn encode(days: i64) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_date(&mut buf, days);
+        buf
+    }
+
+    // 0001-01-01 and 9999-12-31
+    assert_eq!(encode(0), [0x00, 0x00, 0x00]);
+    assert_eq!(encode(MAX_DAYS), [0xda, 0xb9, 0x37]);
+
+    assert_eq!(encode(-1), encode(0));
+    assert_eq!(encode(i64::MIN), encode(0));
+    assert_eq!(encode(MAX_DAYS + 1), encode(MAX_DAYS));
+    assert_eq!(encode(1 << 24), encode(MAX_DAYS));
+}
+
+#[test]
+fn test_encode_datetime_out_of_range() {
+    fn encode(days: i64, nanos: u64) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_datetime(&mut buf, days, nanos);
+        buf
+    }
+
+    assert_eq!(encode(-1, 12 * 60 * NANOS_PER_MINUTE), encode(0, 0));
+    assert_eq!(encode(MAX_DAYS + 1, 0), encode(MAX_DAYS, MAX_NANOS));
+
+    // 9999-12-31 23:59:59.9999999
+    assert_eq!(
+        encode(MAX_DAYS, MAX_NANOS),
+        [0xff, 0xbf, 0x69, 0x2a, 0xc9, 0xda, 0xb9, 0x37]
+    );
+}
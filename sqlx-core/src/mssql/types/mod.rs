@@ -2,10 +2,27 @@ use crate::encode::{Encode, IsNull};
 use crate::mssql::protocol::type_info::{DataType, TypeInfo};
 use crate::mssql::{Mssql, MssqlTypeInfo};
 
+mod bool;
+mod bytes;
 mod float;
 mod int;
 mod str;
 
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal;
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod datetime;
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "uuid")]
+mod uuid;
+
 impl<'q, T: 'q + Encode<'q, Mssql>> Encode<'q, Mssql> for Option<T> {
     fn produces(&self) -> Option<MssqlTypeInfo> {
         if let Some(v) = self {
@@ -2,7 +2,9 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::mssql::io::MssqlBufMutExt;
-use crate::mssql::protocol::type_info::{Collation, CollationFlags, DataType, TypeInfo};
+use crate::mssql::protocol::type_info::{
+    Collation, CollationFlags, DataType, TypeInfo, MAX_LEN, MAX_SHORT_LEN,
+};
 use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
 use crate::types::Type;
 
@@ -20,10 +22,17 @@ impl Type<Mssql> for String {
 
 impl Encode<'_, Mssql> for &'_ str {
     fn produces(&self) -> Option<MssqlTypeInfo> {
-        // an empty string needs to be encoded as `nvarchar(2)`
+        // an empty string needs to be encoded as `nvarchar(2)` and a string that is too
+        // long for `nvarchar(4000)` as `nvarchar(max)`
+        let size = match (self.len() * 2) as u32 {
+            0 => 2,
+            size if size > MAX_SHORT_LEN => MAX_LEN,
+            size => size,
+        };
+
         Some(MssqlTypeInfo(TypeInfo {
             ty: DataType::NVarChar,
-            size: ((self.len() * 2) as u32).max(2),
+            size,
             scale: 0,
             precision: 0,
             collation: Some(Collation {
@@ -64,6 +73,9 @@ impl Decode<'_, Mssql> for String {
                 | DataType::VarChar
                 | DataType::BigChar
                 | DataType::Char
+                | DataType::NText
+                | DataType::Text
+                | DataType::Xml
         )
     }
 
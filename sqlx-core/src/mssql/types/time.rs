@@ -0,0 +1,148 @@
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::DataType;
+use crate::mssql::types::datetime;
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+impl Type<Mssql> for Date {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::DateN)
+    }
+}
+
+impl Encode<'_, Mssql> for Date {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        datetime::encode_date(buf, days(*self));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for Date {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::DateN)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(date(datetime::decode_date(&value)?))
+    }
+}
+
+impl Type<Mssql> for Time {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::TimeN)
+    }
+}
+
+impl Encode<'_, Mssql> for Time {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        datetime::encode_time(buf, nanos(*self));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for Time {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::TimeN)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(time(datetime::decode_time(&value)?))
+    }
+}
+
+impl Type<Mssql> for PrimitiveDateTime {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::DateTime2N)
+    }
+}
+
+impl Encode<'_, Mssql> for PrimitiveDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        datetime::encode_datetime(buf, days(self.date()), nanos(self.time()));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for PrimitiveDateTime {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(
+            ty.0.ty,
+            DataType::DateTime2N
+                | DataType::DateTime
+                | DataType::SmallDateTime
+                | DataType::DateTimeN
+        )
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let (days, nanos) = datetime::decode_datetime(&value)?;
+
+        Ok(PrimitiveDateTime::new(date(days), time(nanos)))
+    }
+}
+
+impl Type<Mssql> for OffsetDateTime {
+    fn type_info() -> MssqlTypeInfo {
+        datetime::type_info(DataType::DateTimeOffsetN)
+    }
+}
+
+impl Encode<'_, Mssql> for OffsetDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        // the date and time are sent in UTC
+        let utc = self.to_offset(UtcOffset::UTC);
+
+        datetime::encode_datetime_offset(
+            buf,
+            days(utc.date()),
+            nanos(utc.time()),
+            self.offset().as_minutes(),
+        );
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for OffsetDateTime {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::DateTimeOffsetN)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let (days, nanos, offset) = datetime::decode_datetime_offset(&value)?;
+
+        Ok(PrimitiveDateTime::new(date(days), time(nanos))
+            .assume_utc()
+            .to_offset(UtcOffset::minutes(offset)))
+    }
+}
+
+fn epoch() -> Date {
+    // unwrap: 0001-01-01 is a valid date
+    Date::try_from_ymd(1, 1, 1).unwrap()
+}
+
+// the number of days since 0001-01-01
+fn days(date: Date) -> i64 {
+    (date - epoch()).whole_days()
+}
+
+// the number of nanoseconds since midnight
+fn nanos(time: Time) -> u64 {
+    (time - Time::midnight()).whole_nanoseconds() as u64
+}
+
+fn date(days: i64) -> Date {
+    epoch() + Duration::days(days)
+}
+
+fn time(nanos: u64) -> Time {
+    Time::midnight() + Duration::nanoseconds(nanos as i64)
+}
@@ -0,0 +1,52 @@
+use byteorder::{ByteOrder, LittleEndian};
+use uuid::Uuid;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo, MssqlValueRef};
+use crate::types::Type;
+
+impl Type<Mssql> for Uuid {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::Guid, 16))
+    }
+}
+
+// a UNIQUEIDENTIFIER stores its first three fields in little-endian byte order
+
+impl Encode<'_, Mssql> for Uuid {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        let (d1, d2, d3, d4) = self.as_fields();
+
+        buf.extend(&d1.to_le_bytes());
+        buf.extend(&d2.to_le_bytes());
+        buf.extend(&d3.to_le_bytes());
+        buf.extend(d4);
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, Mssql> for Uuid {
+    fn accepts(ty: &MssqlTypeInfo) -> bool {
+        matches!(ty.0.ty, DataType::Guid)
+    }
+
+    fn decode(value: MssqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let buf = value.as_bytes()?;
+
+        if buf.len() != 16 {
+            return Err(format!("invalid length {} for UNIQUEIDENTIFIER", buf.len()).into());
+        }
+
+        Uuid::from_fields(
+            LittleEndian::read_u32(&buf[..4]),
+            LittleEndian::read_u16(&buf[4..6]),
+            LittleEndian::read_u16(&buf[6..8]),
+            &buf[8..],
+        )
+        .map_err(Into::into)
+    }
+}
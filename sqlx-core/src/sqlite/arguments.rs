@@ -42,6 +42,17 @@ impl<'q> Arguments<'q> for SqliteArguments<'q> {
 }
 
 impl SqliteArguments<'_> {
+    // the arguments are bound on the worker thread of the connection
+    pub(crate) fn into_static(self) -> SqliteArguments<'static> {
+        SqliteArguments {
+            values: self
+                .values
+                .into_iter()
+                .map(SqliteArgumentValue::into_static)
+                .collect(),
+        }
+    }
+
     pub(super) fn bind(&self, statement: &SqliteStatement) -> Result<(), Error> {
         let mut arg_i = 0;
         for handle in &statement.handles {
@@ -77,6 +88,19 @@ impl SqliteArguments<'_> {
 }
 
 impl SqliteArgumentValue<'_> {
+    fn into_static(self) -> SqliteArgumentValue<'static> {
+        use SqliteArgumentValue::*;
+
+        match self {
+            Text(v) => Text(Cow::Owned(v.into_owned())),
+            Blob(v) => Blob(Cow::Owned(v.into_owned())),
+            Int(v) => Int(v),
+            Int64(v) => Int64(v),
+            Double(v) => Double(v),
+            Null => Null,
+        }
+    }
+
     fn bind(&self, handle: &StatementHandle, i: usize) -> Result<(), Error> {
         use SqliteArgumentValue::*;
 
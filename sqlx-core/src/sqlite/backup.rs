@@ -0,0 +1,369 @@
+use std::future::Future;
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use libsqlite3_sys::{
+    sqlite3, sqlite3_backup, sqlite3_backup_finish, sqlite3_backup_init, sqlite3_backup_pagecount,
+    sqlite3_backup_remaining, sqlite3_backup_step, SQLITE_BUSY, SQLITE_DONE, SQLITE_LOCKED,
+    SQLITE_OK,
+};
+use sqlx_rt::sleep;
+
+use crate::connection::Connect;
+use crate::error::Error;
+use crate::sqlite::connection::ConnectionWorker;
+use crate::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteError};
+
+// the sample program in the SQLite documentation waits 250 ms before retrying a step that
+// could not get a lock
+// <https://www.sqlite.org/backup.html>
+const BUSY_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+const DEFAULT_PAGES_PER_STEP: c_int = 100;
+
+/// The destination of an online backup started with [`SqliteConnection::backup_to`].
+#[derive(Debug)]
+pub enum SqliteBackupTarget<'c> {
+    /// The main database of an open connection, which is overwritten.
+    Connection(&'c mut SqliteConnection),
+
+    /// A database file, which is created if it does not exist and overwritten if it does.
+    Path(PathBuf),
+}
+
+impl<'c> From<&'c mut SqliteConnection> for SqliteBackupTarget<'c> {
+    fn from(conn: &'c mut SqliteConnection) -> Self {
+        SqliteBackupTarget::Connection(conn)
+    }
+}
+
+impl From<PathBuf> for SqliteBackupTarget<'_> {
+    fn from(path: PathBuf) -> Self {
+        SqliteBackupTarget::Path(path)
+    }
+}
+
+impl From<&'_ Path> for SqliteBackupTarget<'_> {
+    fn from(path: &Path) -> Self {
+        SqliteBackupTarget::Path(path.to_owned())
+    }
+}
+
+impl From<&'_ str> for SqliteBackupTarget<'_> {
+    fn from(path: &str) -> Self {
+        SqliteBackupTarget::Path(path.into())
+    }
+}
+
+/// The progress of an online backup, reported after each step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteBackupProgress {
+    remaining: u32,
+    page_count: u32,
+}
+
+impl SqliteBackupProgress {
+    /// The number of pages still to be copied.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// The total number of pages in the source database.
+    pub fn page_count(&self) -> u32 {
+        self.page_count
+    }
+}
+
+/// An online backup of the main database of a connection, created by
+/// [`SqliteConnection::backup_to`].
+///
+/// The backup runs when this is awaited. Pages are copied a few at a time, releasing the
+/// lock on the source database between steps so other connections may keep writing to it.
+/// A step that modifies the source restarts the backup, unless it was made through the
+/// connection being backed up.
+#[must_use = "a backup does nothing unless awaited"]
+pub struct SqliteBackup<'c> {
+    backup: Option<Backup<'c>>,
+    future: Option<BoxFuture<'c, Result<(), Error>>>,
+}
+
+struct Backup<'c> {
+    source: &'c mut SqliteConnection,
+    target: SqliteBackupTarget<'c>,
+    pages_per_step: c_int,
+    progress: Option<Box<dyn FnMut(SqliteBackupProgress) + Send + 'c>>,
+}
+
+impl<'c> SqliteBackup<'c> {
+    pub(crate) fn new(source: &'c mut SqliteConnection, target: SqliteBackupTarget<'c>) -> Self {
+        Self {
+            backup: Some(Backup {
+                source,
+                target,
+                pages_per_step: DEFAULT_PAGES_PER_STEP,
+                progress: None,
+            }),
+            future: None,
+        }
+    }
+
+    /// Sets the number of pages to copy in each step.
+    ///
+    /// Passing `0` copies the whole database in a single step, which holds the lock on the
+    /// source database until the backup is complete. Defaults to 100.
+    pub fn pages_per_step(mut self, pages: u32) -> Self {
+        if let Some(backup) = &mut self.backup {
+            backup.pages_per_step = match pages {
+                0 => -1,
+                pages => pages.min(c_int::MAX as u32) as c_int,
+            };
+        }
+
+        self
+    }
+
+    /// Sets a callback to be invoked with the progress of the backup after each step.
+    pub fn on_progress<F>(mut self, progress: F) -> Self
+    where
+        F: FnMut(SqliteBackupProgress) + Send + 'c,
+    {
+        if let Some(backup) = &mut self.backup {
+            backup.progress = Some(Box::new(progress));
+        }
+
+        self
+    }
+}
+
+impl<'c> Future for SqliteBackup<'c> {
+    type Output = Result<(), Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.future.is_none() {
+            let backup = self
+                .backup
+                .take()
+                .expect("SqliteBackup polled after completion");
+
+            self.future = Some(Box::pin(backup.run()));
+        }
+
+        match &mut self.future {
+            Some(future) => future.as_mut().poll(cx),
+            None => unreachable!(),
+        }
+    }
+}
+
+impl Backup<'_> {
+    async fn run(self) -> Result<(), Error> {
+        let Backup {
+            source,
+            target,
+            pages_per_step,
+            mut progress,
+        } = self;
+
+        let mut opened;
+
+        let destination = match target {
+            SqliteBackupTarget::Connection(conn) => conn,
+
+            SqliteBackupTarget::Path(path) => {
+                let options = SqliteConnectOptions::new().filename(path);
+                opened = SqliteConnection::connect_with(&options).await?;
+
+                &mut opened
+            }
+        };
+
+        let mut running = Running::init(&destination.worker, &source.worker).await?;
+
+        loop {
+            let (status, step_progress) = running.step(pages_per_step).await?;
+
+            match status {
+                SQLITE_OK | SQLITE_DONE => {
+                    if let Some(progress) = &mut progress {
+                        progress(step_progress);
+                    }
+
+                    if status == SQLITE_DONE {
+                        break;
+                    }
+                }
+
+                // SQLITE_BUSY or SQLITE_LOCKED
+                _ => {
+                    sleep(BUSY_RETRY_DELAY).await;
+                }
+            }
+        }
+
+        running.finish().await
+    }
+}
+
+// a backup from the connection of one worker to the connection of another; each call is run
+// on the worker of the destination, along with the connection of the source
+struct Running<'a> {
+    destination: &'a ConnectionWorker,
+    source: &'a ConnectionWorker,
+
+    // taken while a step is in progress
+    handle: Option<BackupHandle>,
+}
+
+impl<'a> Running<'a> {
+    async fn init(
+        destination: &'a ConnectionWorker,
+        source: &'a ConnectionWorker,
+    ) -> Result<Running<'a>, Error> {
+        let handle = destination
+            .run_with(source, |destination, source| {
+                BackupHandle::init(source.handle.as_ptr(), destination.handle.as_ptr())
+            })
+            .await?;
+
+        Ok(Running {
+            destination,
+            source,
+            handle: Some(handle),
+        })
+    }
+
+    // copies up to the given number of pages; returns the status of the step, which is
+    // SQLITE_BUSY or SQLITE_LOCKED if it should be retried later
+    async fn step(
+        &mut self,
+        pages_per_step: c_int,
+    ) -> Result<(c_int, SqliteBackupProgress), Error> {
+        let handle = self.handle.take().expect("backup step is not in progress");
+
+        // the handle is moved into the command and back out so a dropped future cannot
+        // finish it while it is stepping
+        let (handle, status, progress) = self
+            .destination
+            .run_with(self.source, move |destination, _| {
+                // <https://www.sqlite.org/c3ref/backup_finish.html#sqlite3backupstep>
+                let status = unsafe { sqlite3_backup_step(handle.0.as_ptr(), pages_per_step) };
+
+                match status {
+                    SQLITE_OK | SQLITE_DONE | SQLITE_BUSY | SQLITE_LOCKED => {
+                        let progress = handle.progress();
+
+                        Ok((handle, status, progress))
+                    }
+
+                    _ => {
+                        // the error code of the step is left on the destination connection
+                        drop(handle);
+
+                        Err(SqliteError::new(destination.handle.as_ptr()).into())
+                    }
+                }
+            })
+            .await?;
+
+        self.handle = Some(handle);
+
+        Ok((status, progress))
+    }
+
+    async fn finish(mut self) -> Result<(), Error> {
+        let handle = self.handle.take().expect("backup step is not in progress");
+
+        self.destination
+            .run_with(self.source, move |destination, _| {
+                handle.finish(destination.handle.as_ptr())
+            })
+            .await
+    }
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        // a backup that is abandoned between steps is finished before either connection
+        // runs anything else
+        if let Some(handle) = self.handle.take() {
+            drop(self.destination.run_with(self.source, move |_, _| {
+                drop(handle);
+                Ok(())
+            }));
+        }
+    }
+}
+
+// an in-progress backup; SQLite requires that it is finished before either
+// connection is closed
+struct BackupHandle(NonNull<sqlite3_backup>);
+
+// the backup is only ever used on the worker thread of the destination, while the worker of
+// the source is held
+unsafe impl Send for BackupHandle {}
+
+impl BackupHandle {
+    fn init(source: *mut sqlite3, destination: *mut sqlite3) -> Result<Self, Error> {
+        // <https://www.sqlite.org/c3ref/backup_finish.html#sqlite3backupinit>
+        let handle = unsafe {
+            sqlite3_backup_init(
+                destination,
+                b"main\0".as_ptr() as *const _,
+                source,
+                b"main\0".as_ptr() as *const _,
+            )
+        };
+
+        match NonNull::new(handle) {
+            Some(handle) => Ok(BackupHandle(handle)),
+
+            // the error is left on the destination connection
+            None => Err(SqliteError::new(destination).into()),
+        }
+    }
+
+    fn progress(&self) -> SqliteBackupProgress {
+        // <https://www.sqlite.org/c3ref/backup_finish.html#sqlite3backupremaining>
+        let (remaining, page_count) = unsafe {
+            (
+                sqlite3_backup_remaining(self.0.as_ptr()),
+                sqlite3_backup_pagecount(self.0.as_ptr()),
+            )
+        };
+
+        SqliteBackupProgress {
+            remaining: remaining as u32,
+            page_count: page_count as u32,
+        }
+    }
+
+    fn finish(self, destination: *mut sqlite3) -> Result<(), Error> {
+        let handle = self.0.as_ptr();
+
+        // the backup is released by [sqlite3_backup_finish] below
+        std::mem::forget(self);
+
+        // <https://www.sqlite.org/c3ref/backup_finish.html#sqlite3backupfinish>
+        let status = unsafe { sqlite3_backup_finish(handle) };
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::new(destination).into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for BackupHandle {
+    fn drop(&mut self) {
+        // NOTE: ignore the failure here, it was already reported by the step that failed
+        unsafe {
+            sqlite3_backup_finish(self.0.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::io::{self, SeekFrom};
+use std::os::raw::c_int;
+use std::pin::Pin;
+use std::ptr::{null_mut, NonNull};
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use libsqlite3_sys::{
+    sqlite3, sqlite3_blob, sqlite3_blob_bytes, sqlite3_blob_close, sqlite3_blob_open,
+    sqlite3_blob_read, sqlite3_blob_reopen, sqlite3_blob_write, SQLITE_OK, SQLITE_READONLY,
+};
+use sqlx_rt::{AsyncRead, AsyncSeek, AsyncWrite};
+
+use crate::error::Error;
+use crate::sqlite::connection::CommandSender;
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+/// A handle for incremental I/O on a single BLOB value, created by
+/// [`SqliteConnection::open_blob`].
+///
+/// A BLOB cannot change size through this handle. Reads stop at the end of the value and
+/// writes past the end are refused; use `zeroblob(N)` in an `INSERT` or `UPDATE` to reserve
+/// space for a value that is then written incrementally.
+///
+/// If the row is modified or deleted by anything other than this handle, every following
+/// read or write fails with `SQLITE_ABORT`.
+pub struct SqliteBlob<'c> {
+    state: State,
+    offset: u64,
+    len: u64,
+
+    // the handle must not outlive the connection, nor be used at the same time
+    conn: &'c mut SqliteConnection,
+}
+
+enum State {
+    // [None] once the worker of the connection has crashed
+    Idle(Option<Inner>),
+    Busy(
+        Op,
+        BoxFuture<'static, Result<(Inner, io::Result<usize>), Error>>,
+    ),
+}
+
+// the BLOB handle along with a buffer that is moved to the worker thread for each operation
+struct Inner {
+    handle: BlobHandle,
+    buf: Vec<u8>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Read,
+    Write,
+}
+
+impl SqliteConnection {
+    /// Opens the BLOB in `column` of the row with the given `rowid` in `table` for
+    /// incremental I/O.
+    ///
+    /// The returned [`SqliteBlob`] implements `AsyncRead`, `AsyncWrite` and `AsyncSeek`.
+    /// Each read or write is run on the worker thread of the connection.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # use sqlx_rt::AsyncReadExt;
+    /// # async fn example(conn: &mut SqliteConnection) -> Result<(), Error> {
+    /// let mut blob = conn.open_blob("attachments", "data", 1, true).await?;
+    ///
+    /// let mut header = [0_u8; 16];
+    /// blob.read_exact(&mut header).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open_blob(
+        &mut self,
+        table: &str,
+        column: &str,
+        rowid: i64,
+        read_only: bool,
+    ) -> Result<SqliteBlob<'_>, Error> {
+        let table = CString::new(table)
+            .map_err(|_| err_protocol!("table name {:?} contains a NUL byte", table))?;
+
+        let column = CString::new(column)
+            .map_err(|_| err_protocol!("column name {:?} contains a NUL byte", column))?;
+
+        let worker = self.worker.sender();
+
+        let (handle, len) = self
+            .worker
+            .run(move |conn| {
+                let db = DbHandle(conn.handle.as_ptr());
+                let mut handle = null_mut();
+
+                // <https://www.sqlite.org/c3ref/blob_open.html>
+                let status = unsafe {
+                    sqlite3_blob_open(
+                        db.0,
+                        b"main\0".as_ptr() as *const _,
+                        table.as_ptr(),
+                        column.as_ptr(),
+                        rowid,
+                        if read_only { 0 } else { 1 },
+                        &mut handle,
+                    )
+                };
+
+                // on failure, the handle is set to NULL and there is nothing to close
+                if status != SQLITE_OK {
+                    return Err(Error::Database(Box::new(SqliteError::new(db.0))));
+                }
+
+                let handle = NonNull::new(handle)
+                    .map(|handle| BlobHandle { db, handle, worker })
+                    .ok_or_else(|| err_protocol!("SQLite returned a NULL BLOB handle"))?;
+
+                let len = handle.len();
+
+                Ok((handle, len))
+            })
+            .await?;
+
+        Ok(SqliteBlob::new(self, handle, len))
+    }
+}
+
+impl<'c> SqliteBlob<'c> {
+    fn new(conn: &'c mut SqliteConnection, handle: BlobHandle, len: u64) -> Self {
+        Self {
+            len,
+            state: State::Idle(Some(Inner {
+                handle,
+                buf: Vec::new(),
+            })),
+            offset: 0,
+            conn,
+        }
+    }
+
+    /// Returns the size of the BLOB in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the BLOB is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Moves this handle to the same column of another row, without the cost of opening a
+    /// new handle. The position is reset to the start of the BLOB.
+    pub async fn reopen(&mut self, rowid: i64) -> Result<(), Error> {
+        // let any read or write still in progress finish first
+        futures_util::future::poll_fn(|cx| self.poll_idle(cx)).await?;
+
+        let mut inner = match &mut self.state {
+            State::Idle(inner) => inner.take().ok_or(Error::WorkerCrashed)?,
+            State::Busy(..) => unreachable!(),
+        };
+
+        let (inner, result, len) = self
+            .conn
+            .worker
+            .run(move |_| {
+                // <https://www.sqlite.org/c3ref/blob_reopen.html>
+                let status = unsafe { sqlite3_blob_reopen(inner.handle.as_ptr(), rowid) };
+
+                let result = if status == SQLITE_OK {
+                    inner.buf.clear();
+                    Ok(())
+                } else {
+                    Err(Error::Database(Box::new(inner.handle.last_error())))
+                };
+
+                let len = inner.handle.len();
+
+                Ok((inner, result, len))
+            })
+            .await?;
+
+        self.len = len;
+        self.offset = 0;
+        self.state = State::Idle(Some(inner));
+
+        result
+    }
+
+    // waits for any read or write in progress to complete, discarding its result
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let State::Busy(_, future) = &mut self.state {
+            let result = futures_core::ready!(future.as_mut().poll(cx));
+
+            self.state = State::Idle(result.ok().map(|(inner, _)| inner));
+        }
+
+        match self.state {
+            State::Idle(None) => Poll::Ready(Err(crashed())),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_op(&mut self, cx: &mut Context<'_>, op: Op, data: &[u8]) -> Poll<io::Result<usize>> {
+        if matches!(self.state, State::Busy(pending, _) if pending != op) {
+            futures_core::ready!(self.poll_idle(cx))?;
+        }
+
+        if let State::Idle(inner) = &mut self.state {
+            let available = self.len.saturating_sub(self.offset);
+            let n = (data.len() as u64).min(available) as usize;
+
+            if n == 0 {
+                return Poll::Ready(Ok(0));
+            }
+
+            let mut inner = inner.take().ok_or_else(crashed)?;
+
+            // SAFE: the offset is less than the size of the BLOB, which is a [c_int]
+            let offset = self.offset as c_int;
+
+            inner.buf.clear();
+
+            match op {
+                Op::Read => inner.buf.resize(n, 0),
+                Op::Write => inner.buf.extend_from_slice(&data[..n]),
+            }
+
+            self.state = State::Busy(
+                op,
+                Box::pin(self.conn.worker.run(move |_| {
+                    let result = match op {
+                        Op::Read => inner.handle.read(&mut inner.buf, offset),
+                        Op::Write => inner.handle.write(&inner.buf, offset),
+                    };
+
+                    Ok((inner, result.map(|_| n)))
+                })),
+            );
+        }
+
+        let (inner, result) = match &mut self.state {
+            State::Busy(_, future) => match futures_core::ready!(future.as_mut().poll(cx)) {
+                Ok(done) => done,
+
+                Err(_) => {
+                    self.state = State::Idle(None);
+
+                    return Poll::Ready(Err(crashed()));
+                }
+            },
+
+            State::Idle(_) => unreachable!(),
+        };
+
+        if let Ok(n) = result {
+            self.offset += n as u64;
+        }
+
+        self.state = State::Idle(Some(inner));
+
+        Poll::Ready(result)
+    }
+
+    fn buffer(&self) -> &[u8] {
+        match &self.state {
+            State::Idle(Some(inner)) => &inner.buf,
+            _ => &[],
+        }
+    }
+
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let offset = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => offset_by(self.len, delta),
+            SeekFrom::Current(delta) => offset_by(self.offset, delta),
+        };
+
+        match offset {
+            Some(offset) if offset <= self.len => {
+                self.offset = offset;
+                Ok(offset)
+            }
+
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek to a position outside of the BLOB",
+            )),
+        }
+    }
+}
+
+// the connection can no longer be reached
+fn crashed() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, Error::WorkerCrashed)
+}
+
+fn offset_by(base: u64, delta: i64) -> Option<u64> {
+    if delta < 0 {
+        base.checked_sub(delta.wrapping_neg() as u64)
+    } else {
+        base.checked_add(delta as u64)
+    }
+}
+
+impl AsyncRead for SqliteBlob<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let read = futures_core::ready!(self.poll_op(cx, Op::Read, buf))?;
+
+        // the read was sized by the buffer of the first poll; if we are polled again with
+        // a smaller buffer, the excess is read again by the next call
+        let n = read.min(buf.len());
+        self.offset -= (read - n) as u64;
+
+        buf[..n].copy_from_slice(&self.buffer()[..n]);
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for SqliteBlob<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_op(cx, Op::Write, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // writes go straight to the database, there is nothing buffered
+        self.poll_idle(cx)
+    }
+
+    #[cfg(any(feature = "runtime-actix", feature = "runtime-tokio"))]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_idle(cx)
+    }
+
+    #[cfg(feature = "runtime-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_idle(cx)
+    }
+}
+
+#[cfg(any(feature = "runtime-actix", feature = "runtime-tokio"))]
+impl AsyncSeek for SqliteBlob<'_> {
+    fn start_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        position: SeekFrom,
+    ) -> Poll<io::Result<()>> {
+        futures_core::ready!(self.poll_idle(cx))?;
+
+        Poll::Ready(self.seek(position).map(|_| ()))
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.offset))
+    }
+}
+
+#[cfg(feature = "runtime-async-std")]
+impl AsyncSeek for SqliteBlob<'_> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        position: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        futures_core::ready!(self.poll_idle(cx))?;
+
+        Poll::Ready(self.seek(position))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DbHandle(*mut sqlite3);
+
+// the database handle is only used on the worker thread of the connection
+unsafe impl Send for DbHandle {}
+
+struct BlobHandle {
+    db: DbHandle,
+    handle: NonNull<sqlite3_blob>,
+
+    // the handle is dropped by the BLOB, which does not borrow the connection while it is
+    // dropped, or on the worker thread
+    worker: CommandSender,
+}
+
+unsafe impl Send for BlobHandle {}
+
+impl BlobHandle {
+    fn as_ptr(&self) -> *mut sqlite3_blob {
+        self.handle.as_ptr()
+    }
+
+    fn len(&self) -> u64 {
+        // <https://www.sqlite.org/c3ref/blob_bytes.html>
+        unsafe { sqlite3_blob_bytes(self.as_ptr()) as u64 }
+    }
+
+    fn last_error(&self) -> SqliteError {
+        SqliteError::new(self.db.0)
+    }
+
+    fn read(&self, buf: &mut [u8], offset: c_int) -> io::Result<()> {
+        let n = c_int::try_from(buf.len()).unwrap_or(c_int::MAX);
+
+        // <https://www.sqlite.org/c3ref/blob_read.html>
+        let status =
+            unsafe { sqlite3_blob_read(self.as_ptr(), buf.as_mut_ptr() as *mut _, n, offset) };
+
+        self.check(status)
+    }
+
+    fn write(&self, buf: &[u8], offset: c_int) -> io::Result<()> {
+        let n = c_int::try_from(buf.len()).unwrap_or(c_int::MAX);
+
+        // <https://www.sqlite.org/c3ref/blob_write.html>
+        let status =
+            unsafe { sqlite3_blob_write(self.as_ptr(), buf.as_ptr() as *const _, n, offset) };
+
+        self.check(status)
+    }
+
+    fn check(&self, status: c_int) -> io::Result<()> {
+        let kind = match status {
+            SQLITE_OK => return Ok(()),
+
+            // writing to a BLOB that was opened as read-only
+            SQLITE_READONLY => io::ErrorKind::PermissionDenied,
+
+            _ => io::ErrorKind::Other,
+        };
+
+        Err(io::Error::new(kind, self.last_error()))
+    }
+}
+
+impl Drop for BlobHandle {
+    fn drop(&mut self) {
+        let handle = Closing(self.handle);
+
+        // NOTE: ignore a crashed worker; its connection could not be closed either
+        let _ = self.worker.run_detached(move |_| {
+            // NOTE: ignore the failure here, it was already reported by the read or write
+            // <https://www.sqlite.org/c3ref/blob_close.html>
+            unsafe {
+                sqlite3_blob_close(handle.0.as_ptr());
+            }
+        });
+    }
+}
+
+// a BLOB handle on its way to be closed on the worker thread
+struct Closing(NonNull<sqlite3_blob>);
+
+unsafe impl Send for Closing {}
@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+use std::ffi::CString;
+use std::fmt::{self, Debug, Formatter};
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_collation_needed, sqlite3_create_collation_v2, SQLITE_OK, SQLITE_UTF8,
+};
+
+use crate::error::Error;
+use crate::sqlite::SqliteError;
+
+type CompareFn = dyn Fn(&str, &str) -> Ordering + Send + Sync + 'static;
+
+// a collating sequence, registered with [SqliteConnectOptions::collation]
+#[derive(Clone)]
+pub(crate) struct Collation {
+    name: Arc<str>,
+    compare: Arc<CompareFn>,
+}
+
+impl Collation {
+    pub(crate) fn new<F>(name: &str, compare: F) -> Self
+    where
+        F: Fn(&str, &str) -> Ordering + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            compare: Arc::new(compare),
+        }
+    }
+
+    pub(crate) fn create(&self, handle: *mut sqlite3) -> Result<(), Error> {
+        let name = CString::new(&*self.name)
+            .map_err(|_| err_protocol!("collation name {:?} contains a NUL byte", self.name))?;
+
+        let compare = Box::into_raw(Box::new(Arc::clone(&self.compare)));
+
+        // <https://www.sqlite.org/c3ref/create_collation.html>
+        let status = unsafe {
+            sqlite3_create_collation_v2(
+                handle,
+                name.as_ptr(),
+                SQLITE_UTF8,
+                compare as *mut c_void,
+                Some(call_compare),
+                Some(destroy),
+            )
+        };
+
+        if status != SQLITE_OK {
+            // unlike with functions, SQLite does not call the destructor when this fails
+            drop(unsafe { Box::from_raw(compare) });
+
+            return Err(Error::Database(Box::new(SqliteError::new(handle))));
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for Collation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Collation")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+unsafe extern "C" fn destroy(compare: *mut c_void) {
+    drop(Box::from_raw(compare as *mut Arc<CompareFn>));
+}
+
+unsafe extern "C" fn call_compare(
+    compare: *mut c_void,
+    left_len: c_int,
+    left: *const c_void,
+    right_len: c_int,
+    right: *const c_void,
+) -> c_int {
+    let compare = &*(compare as *const Arc<CompareFn>);
+
+    // the collation is registered for UTF-8, but TEXT can still hold bytes that are not,
+    // as with `CAST(x'ff' AS TEXT)`; only then is a copy made to replace them
+    let left = String::from_utf8_lossy(bytes(left, left_len));
+    let right = String::from_utf8_lossy(bytes(right, right_len));
+
+    // a collating function cannot report an error and a panic must not unwind into SQLite
+    match catch_unwind(AssertUnwindSafe(|| compare(&left, &right))) {
+        Ok(Ordering::Less) => -1,
+        Ok(Ordering::Equal) => 0,
+        Ok(Ordering::Greater) => 1,
+        Err(_) => process::abort(),
+    }
+}
+
+unsafe fn bytes<'a>(ptr: *const c_void, len: c_int) -> &'a [u8] {
+    if len <= 0 || ptr.is_null() {
+        return &[];
+    }
+
+    slice::from_raw_parts(ptr as *const u8, len as usize)
+}
+
+// registers a byte-wise collation for any collating sequence the connection does not know
+// about, so the query macros can prepare statements that use collations which are only
+// registered at runtime
+pub(crate) fn stub_unknown_collations(handle: *mut sqlite3) -> Result<(), Error> {
+    // <https://www.sqlite.org/c3ref/collation_needed.html>
+    let status = unsafe { sqlite3_collation_needed(handle, ptr::null_mut(), Some(call_needed)) };
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(handle))));
+    }
+
+    Ok(())
+}
+
+unsafe extern "C" fn call_needed(
+    _: *mut c_void,
+    handle: *mut sqlite3,
+    _: c_int,
+    name: *const c_char,
+) {
+    // NOTE: ignore the failure here, preparing the statement will report it
+    sqlite3_create_collation_v2(
+        handle,
+        name,
+        SQLITE_UTF8,
+        ptr::null_mut(),
+        Some(compare_bytes),
+        None,
+    );
+}
+
+unsafe extern "C" fn compare_bytes(
+    _: *mut c_void,
+    left_len: c_int,
+    left: *const c_void,
+    right_len: c_int,
+    right: *const c_void,
+) -> c_int {
+    match bytes(left, left_len).cmp(bytes(right, right_len)) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
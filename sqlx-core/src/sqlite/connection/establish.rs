@@ -1,20 +1,29 @@
+use std::convert::TryFrom;
 use std::io;
+use std::os::raw::c_int;
 use std::ptr::{null, null_mut};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, PoisonError};
 
 use hashbrown::HashMap;
 use libsqlite3_sys::{
     sqlite3_busy_timeout, sqlite3_extended_result_codes, sqlite3_open_v2, SQLITE_OK,
     SQLITE_OPEN_CREATE, SQLITE_OPEN_MEMORY, SQLITE_OPEN_NOMUTEX, SQLITE_OPEN_PRIVATECACHE,
-    SQLITE_OPEN_READWRITE,
+    SQLITE_OPEN_READONLY, SQLITE_OPEN_READWRITE, SQLITE_OPEN_SHAREDCACHE, SQLITE_OPEN_URI,
 };
-use sqlx_rt::blocking;
 
 use crate::error::Error;
+use crate::sqlite::collation::stub_unknown_collations as stub_collations;
 use crate::sqlite::connection::handle::ConnectionHandle;
-use crate::sqlite::statement::StatementWorker;
-use crate::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteError};
+use crate::sqlite::connection::ConnectionState;
+use crate::sqlite::hooks::Hooks;
+use crate::sqlite::{SqliteConnectOptions, SqliteError};
 
-pub(super) async fn establish(options: &SqliteConnectOptions) -> Result<SqliteConnection, Error> {
+// runs on the worker thread of the connection
+pub(super) fn establish(
+    options: &SqliteConnectOptions,
+    interrupt: Arc<AtomicBool>,
+) -> Result<ConnectionState, Error> {
     let mut filename = options
         .filename
         .to_str()
@@ -31,64 +40,109 @@ pub(super) async fn establish(options: &SqliteConnectOptions) -> Result<SqliteCo
     // By default, we connect to an in-memory database.
     // [SQLITE_OPEN_NOMUTEX] will instruct [sqlite3_open_v2] to return an error if it
     // cannot satisfy our wish for a thread-safe, lock-free connection object
-    let mut flags =
-        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_NOMUTEX | SQLITE_OPEN_PRIVATECACHE;
+    // [SQLITE_OPEN_URI] lets a filename starting with `file:` be interpreted as a URI
+    let mut flags = SQLITE_OPEN_NOMUTEX | SQLITE_OPEN_URI;
+
+    flags |= if options.shared_cache {
+        SQLITE_OPEN_SHAREDCACHE
+    } else {
+        SQLITE_OPEN_PRIVATECACHE
+    };
+
+    flags |= if options.read_only {
+        SQLITE_OPEN_READONLY
+    } else if options.create_if_missing {
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE
+    } else {
+        SQLITE_OPEN_READWRITE
+    };
 
     if options.in_memory {
         flags |= SQLITE_OPEN_MEMORY;
     }
 
-    let handle = blocking!({
-        let mut handle = null_mut();
+    // a timeout too large for SQLite is as good as waiting forever
+    let busy_timeout = i32::try_from(options.busy_timeout.as_millis()).unwrap_or(i32::MAX);
 
-        // <https://www.sqlite.org/c3ref/open.html>
-        let mut status = unsafe {
-            sqlite3_open_v2(
-                filename.as_bytes().as_ptr() as *const _,
-                &mut handle,
-                flags,
-                null(),
-            )
-        };
+    let handle = open(&filename, flags)?;
 
-        if handle.is_null() {
-            // Failed to allocate memory
-            panic!("SQLite is unable to allocate memory to hold the sqlite3 object");
+    if let Some(keep_alive) = &options.keep_alive {
+        // a shared in-memory database is deleted as soon as its last connection is
+        // closed; hold on to one more connection for as long as the options live
+        let mut keep_alive = keep_alive.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if keep_alive.is_none() {
+            *keep_alive = Some(open(&filename, flags)?);
         }
+    }
 
-        // SAFE: tested for NULL just above
-        // This allows any returns below to close this handle with RAII
-        let handle = unsafe { ConnectionHandle::new(handle) };
+    // Enable extended result codes
+    // https://www.sqlite.org/c3ref/extended_result_codes.html
+    unsafe {
+        // NOTE: ignore the failure here
+        sqlite3_extended_result_codes(handle.0.as_ptr(), 1);
+    }
 
-        if status != SQLITE_OK {
-            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
-        }
+    // Configure a busy timeout
+    // This causes SQLite to automatically sleep in increasing intervals until the time
+    // when there is something locked during [sqlite3_step]. This is sync. but we only
+    // run [sqlite3_step] on the worker thread so its okay.
+    let status = unsafe { sqlite3_busy_timeout(handle.0.as_ptr(), busy_timeout) };
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+    }
 
-        // Enable extended result codes
-        // https://www.sqlite.org/c3ref/extended_result_codes.html
-        unsafe {
-            // NOTE: ignore the failure here
-            sqlite3_extended_result_codes(handle.0.as_ptr(), 1);
-        }
+    for extension in &options.extensions {
+        extension.load(handle.as_ptr())?;
+    }
 
-        // Configure a busy timeout
-        // This causes SQLite to automatically sleep in increasing intervals until the time
-        // when there is something locked during [sqlite3_step]. This is sync. but we only
-        // run [sqlite3_step] in [blocking!] so its okay.
-        // TODO: Allow this timeout to be configured in SqliteOptions
-        status = unsafe { sqlite3_busy_timeout(handle.0.as_ptr(), 5000) };
-        if status != SQLITE_OK {
-            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
-        }
+    for function in &options.functions {
+        function.register(handle.as_ptr())?;
+    }
 
-        Ok(handle)
-    })?;
+    for collation in &options.collations {
+        collation.create(handle.as_ptr())?;
+    }
+
+    if options.stub_unknown_collations {
+        stub_collations(handle.as_ptr())?;
+    }
 
-    Ok(SqliteConnection {
+    Ok(ConnectionState {
         handle,
-        worker: StatementWorker::new(),
         statements: HashMap::new(),
         statement: None,
         scratch_row_column_names: Default::default(),
+        hooks: Hooks::default(),
+        interrupt,
     })
 }
+
+fn open(filename: &str, flags: c_int) -> Result<ConnectionHandle, Error> {
+    let mut handle = null_mut();
+
+    // <https://www.sqlite.org/c3ref/open.html>
+    let status = unsafe {
+        sqlite3_open_v2(
+            filename.as_bytes().as_ptr() as *const _,
+            &mut handle,
+            flags,
+            null(),
+        )
+    };
+
+    if handle.is_null() {
+        // Failed to allocate memory
+        panic!("SQLite is unable to allocate memory to hold the sqlite3 object");
+    }
+
+    // SAFE: tested for NULL just above
+    // This allows any returns below to close this handle with RAII
+    let handle = unsafe { ConnectionHandle::new(handle) };
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+    }
+
+    Ok(handle)
+}
@@ -0,0 +1,1173 @@
+use hashbrown::{HashMap, HashSet};
+
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::query_as::query_as;
+use crate::query_scalar::query_scalar;
+use crate::row::Row;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{SqliteConnection, SqliteTypeInfo};
+use crate::value::ValueRef;
+
+// the opcodes of the virtual machine that the analysis understands
+// <https://www.sqlite.org/opcode.html>
+
+const OP_INIT: &str = "Init";
+const OP_GOTO: &str = "Goto";
+const OP_GOSUB: &str = "Gosub";
+const OP_RETURN: &str = "Return";
+const OP_INIT_COROUTINE: &str = "InitCoroutine";
+const OP_YIELD: &str = "Yield";
+const OP_END_COROUTINE: &str = "EndCoroutine";
+const OP_JUMP: &str = "Jump";
+const OP_HALT: &str = "Halt";
+const OP_RESULT_ROW: &str = "ResultRow";
+
+const OP_IF: &str = "If";
+const OP_IF_NOT: &str = "IfNot";
+const OP_IF_POS: &str = "IfPos";
+const OP_IF_NOT_ZERO: &str = "IfNotZero";
+const OP_IF_NULL_ROW: &str = "IfNullRow";
+const OP_IF_NOT_OPEN: &str = "IfNotOpen";
+const OP_IF_NO_HOPE: &str = "IfNoHope";
+const OP_IF_SMALLER: &str = "IfSmaller";
+const OP_IS_NULL: &str = "IsNull";
+const OP_NOT_NULL: &str = "NotNull";
+const OP_EQ: &str = "Eq";
+const OP_NE: &str = "Ne";
+const OP_LT: &str = "Lt";
+const OP_LE: &str = "Le";
+const OP_GT: &str = "Gt";
+const OP_GE: &str = "Ge";
+const OP_ELSE_EQ: &str = "ElseEq";
+const OP_ONCE: &str = "Once";
+const OP_MUST_BE_INT: &str = "MustBeInt";
+const OP_REWIND: &str = "Rewind";
+const OP_LAST: &str = "Last";
+const OP_NEXT: &str = "Next";
+const OP_PREV: &str = "Prev";
+const OP_SORT: &str = "Sort";
+const OP_SORTER_SORT: &str = "SorterSort";
+const OP_SORTER_NEXT: &str = "SorterNext";
+const OP_SEEK_LT: &str = "SeekLT";
+const OP_SEEK_LE: &str = "SeekLE";
+const OP_SEEK_GE: &str = "SeekGE";
+const OP_SEEK_GT: &str = "SeekGT";
+const OP_SEEK_ROWID: &str = "SeekRowid";
+const OP_NOT_EXISTS: &str = "NotExists";
+const OP_FOUND: &str = "Found";
+const OP_NOT_FOUND: &str = "NotFound";
+const OP_NO_CONFLICT: &str = "NoConflict";
+const OP_IDX_LT: &str = "IdxLT";
+const OP_IDX_LE: &str = "IdxLE";
+const OP_IDX_GT: &str = "IdxGT";
+const OP_IDX_GE: &str = "IdxGE";
+const OP_ROW_SET_READ: &str = "RowSetRead";
+const OP_ROW_SET_TEST: &str = "RowSetTest";
+const OP_DECR_JUMP_ZERO: &str = "DecrJumpZero";
+const OP_FILTER: &str = "Filter";
+const OP_VFILTER: &str = "VFilter";
+const OP_VNEXT: &str = "VNext";
+
+const OP_OPEN_READ: &str = "OpenRead";
+const OP_OPEN_WRITE: &str = "OpenWrite";
+const OP_OPEN_DUP: &str = "OpenDup";
+const OP_OPEN_EPHEMERAL: &str = "OpenEphemeral";
+const OP_OPEN_AUTOINDEX: &str = "OpenAutoindex";
+const OP_OPEN_PSEUDO: &str = "OpenPseudo";
+const OP_SORTER_OPEN: &str = "SorterOpen";
+const OP_NULL_ROW: &str = "NullRow";
+const OP_COLUMN: &str = "Column";
+const OP_ROWID: &str = "Rowid";
+const OP_COUNT: &str = "Count";
+const OP_MAKE_RECORD: &str = "MakeRecord";
+const OP_INSERT: &str = "Insert";
+const OP_IDX_INSERT: &str = "IdxInsert";
+const OP_SORTER_INSERT: &str = "SorterInsert";
+const OP_SORTER_DATA: &str = "SorterData";
+
+const OP_NULL: &str = "Null";
+const OP_SOFT_NULL: &str = "SoftNull";
+const OP_INTEGER: &str = "Integer";
+const OP_INT64: &str = "Int64";
+const OP_REAL: &str = "Real";
+const OP_STRING: &str = "String";
+const OP_STRING8: &str = "String8";
+const OP_BLOB: &str = "Blob";
+const OP_VARIABLE: &str = "Variable";
+const OP_MOVE: &str = "Move";
+const OP_COPY: &str = "Copy";
+const OP_SCOPY: &str = "SCopy";
+const OP_INT_COPY: &str = "IntCopy";
+const OP_CAST: &str = "Cast";
+const OP_ADD: &str = "Add";
+const OP_SUBTRACT: &str = "Subtract";
+const OP_MULTIPLY: &str = "Multiply";
+const OP_DIVIDE: &str = "Divide";
+const OP_REMAINDER: &str = "Remainder";
+const OP_CONCAT: &str = "Concat";
+const OP_BIT_AND: &str = "BitAnd";
+const OP_BIT_OR: &str = "BitOr";
+const OP_SHIFT_LEFT: &str = "ShiftLeft";
+const OP_SHIFT_RIGHT: &str = "ShiftRight";
+const OP_BIT_NOT: &str = "BitNot";
+const OP_NOT: &str = "Not";
+const OP_AND: &str = "And";
+const OP_OR: &str = "Or";
+const OP_IS_TRUE: &str = "IsTrue";
+const OP_ZERO_OR_NULL: &str = "ZeroOrNull";
+const OP_FUNCTION: &str = "Function";
+const OP_FUNCTION0: &str = "Function0";
+const OP_PURE_FUNC: &str = "PureFunc";
+const OP_PURE_FUNC0: &str = "PureFunc0";
+const OP_AGG_STEP: &str = "AggStep";
+const OP_AGG_STEP0: &str = "AggStep0";
+const OP_AGG_STEP1: &str = "AggStep1";
+const OP_AGG_FINAL: &str = "AggFinal";
+const OP_COLL_SEQ: &str = "CollSeq";
+
+// instructions that do not write to any register, or only change the affinity of one
+const OP_TRANSACTION: &str = "Transaction";
+const OP_TABLE_LOCK: &str = "TableLock";
+const OP_NOOP: &str = "Noop";
+const OP_EXPLAIN: &str = "Explain";
+const OP_TRACE: &str = "Trace";
+const OP_CLOSE: &str = "Close";
+const OP_AFFINITY: &str = "Affinity";
+const OP_COLUMNS_USED: &str = "ColumnsUsed";
+const OP_CURSOR_HINT: &str = "CursorHint";
+const OP_SEEK_HIT: &str = "SeekHit";
+const OP_DEFERRED_SEEK: &str = "DeferredSeek";
+const OP_FINISH_SEEK: &str = "FinishSeek";
+const OP_PERMUTATION: &str = "Permutation";
+const OP_COMPARE: &str = "Compare";
+const OP_RELEASE_REG: &str = "ReleaseReg";
+
+// flags of P5 on comparisons, see `sqliteInt.h`
+const SQLITE_STOREP2: i64 = 0x20;
+const SQLITE_NULLEQ: i64 = 0x80;
+
+// set in P5 of OpenRead and OpenWrite when P2 is a register instead of a root page
+const OPFLAG_P2ISREG: i64 = 0x10;
+
+// column affinities, in P2 of Cast
+const SQLITE_AFF_BLOB: i64 = 0x41;
+const SQLITE_AFF_TEXT: i64 = 0x42;
+const SQLITE_AFF_INTEGER: i64 = 0x44;
+const SQLITE_AFF_REAL: i64 = 0x45;
+
+// the analysis gives up on programs that take more than this many steps to explore
+const MAX_STEPS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    // the value is always NULL
+    Null,
+    Known(DataType),
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Value {
+    ty: ValueType,
+    not_null: Option<bool>,
+}
+
+impl Value {
+    const NULL: Value = Value {
+        ty: ValueType::Null,
+        not_null: Some(false),
+    };
+
+    const UNKNOWN: Value = Value {
+        ty: ValueType::Unknown,
+        not_null: None,
+    };
+
+    fn of(ty: DataType) -> Self {
+        Value {
+            ty: ValueType::Known(ty),
+            not_null: Some(true),
+        }
+    }
+
+    // the value that is either this one or the other one
+    fn merge(self, other: Value) -> Value {
+        let ty = match (self.ty, other.ty) {
+            (ValueType::Null, ty) | (ty, ValueType::Null) => ty,
+            (ValueType::Known(a), ValueType::Known(b)) if a == b => ValueType::Known(a),
+
+            _ => ValueType::Unknown,
+        };
+
+        Value {
+            ty,
+            not_null: all_not_null(&[self, other]),
+        }
+    }
+}
+
+fn all_not_null(values: &[Value]) -> Option<bool> {
+    let mut all = Some(true);
+
+    for value in values {
+        match value.not_null {
+            Some(false) => return Some(false),
+            Some(true) => {}
+            None => all = None,
+        }
+    }
+
+    all
+}
+
+fn is_integer(ty: DataType) -> bool {
+    matches!(ty, DataType::Int | DataType::Int64 | DataType::Bool)
+}
+
+// the result of an arithmetic operator, which is NULL when either operand is
+fn arithmetic(a: Value, b: Value) -> Value {
+    let ty = match (a.ty, b.ty) {
+        (ValueType::Null, _) | (_, ValueType::Null) => return Value::NULL,
+
+        (ValueType::Known(a), ValueType::Known(b)) if is_integer(a) && is_integer(b) => {
+            ValueType::Known(DataType::Int64)
+        }
+
+        (ValueType::Known(a), ValueType::Known(b))
+            if (is_integer(a) || a == DataType::Float)
+                && (is_integer(b) || b == DataType::Float) =>
+        {
+            ValueType::Known(DataType::Float)
+        }
+
+        _ => ValueType::Unknown,
+    };
+
+    Value {
+        ty,
+        not_null: all_not_null(&[a, b]),
+    }
+}
+
+// the result of an operator that always produces the given type, or NULL from NULL
+fn typed(ty: DataType, operands: &[Value]) -> Value {
+    if operands.iter().any(|value| value.ty == ValueType::Null) {
+        return Value::NULL;
+    }
+
+    Value {
+        ty: ValueType::Known(ty),
+        not_null: all_not_null(operands),
+    }
+}
+
+// the result of a call to one of the built-in scalar functions
+// <https://www.sqlite.org/lang_corefunc.html>
+fn function(name: &str, args: &[Value]) -> Value {
+    match name {
+        "changes" | "last_insert_rowid" | "random" | "total_changes" => Value::of(DataType::Int64),
+
+        "hex" | "quote" | "sqlite_source_id" | "sqlite_version" | "typeof" => {
+            Value::of(DataType::Text)
+        }
+
+        "randomblob" | "zeroblob" => Value::of(DataType::Blob),
+
+        "instr" | "length" | "unicode" => typed(DataType::Int64, args),
+
+        "char" | "lower" | "ltrim" | "printf" | "replace" | "rtrim" | "soundex" | "substr"
+        | "trim" | "upper" => typed(DataType::Text, args),
+
+        "round" => typed(DataType::Float, args),
+
+        "abs" => match args.first() {
+            Some(arg) => arithmetic(*arg, *arg),
+            None => Value::UNKNOWN,
+        },
+
+        // the scalar versions, these return NULL if any argument is NULL
+        "max" | "min" => {
+            let ty = args
+                .iter()
+                .fold(Value::NULL, |value, arg| value.merge(*arg))
+                .ty;
+
+            Value {
+                ty,
+                not_null: all_not_null(args),
+            }
+        }
+
+        // these return NULL on any input they do not understand
+        "date" | "datetime" | "strftime" | "time" => Value {
+            ty: ValueType::Known(DataType::Text),
+            not_null: all_not_null(args).filter(|not_null| !not_null),
+        },
+
+        "julianday" => Value {
+            ty: ValueType::Known(DataType::Float),
+            not_null: all_not_null(args).filter(|not_null| !not_null),
+        },
+
+        _ => Value::UNKNOWN,
+    }
+}
+
+// the result of one of the built-in aggregate functions, given the value it accumulated
+// <https://www.sqlite.org/lang_aggfunc.html>
+fn aggregate(name: &str, accumulator: Value) -> Value {
+    match name {
+        "count" => Value::of(DataType::Int64),
+        "total" => Value::of(DataType::Float),
+
+        // the remaining functions return NULL when there are no rows
+        "avg" => Value {
+            ty: ValueType::Known(DataType::Float),
+            not_null: Some(false),
+        },
+
+        "group_concat" => Value {
+            ty: ValueType::Known(DataType::Text),
+            not_null: Some(false),
+        },
+
+        "sum" => Value {
+            not_null: Some(false),
+            ..arithmetic(accumulator, accumulator)
+        },
+
+        "max" | "min" => Value {
+            ty: accumulator.ty,
+            not_null: Some(false),
+        },
+
+        _ => Value::UNKNOWN,
+    }
+}
+
+// the name of a function as shown in P4, which looks like `name(argc)`
+fn function_name(p4: &str) -> String {
+    p4.split('(')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+// the number of arguments of a function call
+//
+// `AggStep`, and the `Function0` and `PureFunc0` of SQLite before 3.31, have it in P5. Since
+// 3.31, `Function` and `PureFunc` use P5 for flags and only show `name(N)` in P4, where N is the
+// number of arguments the function was declared with; that is negative for a variadic function,
+// such as the scalar `max()`, and the number of arguments of the call is then not known.
+fn function_argc(instruction: &Instruction) -> Option<i64> {
+    match &*instruction.opcode {
+        OP_FUNCTION | OP_PURE_FUNC => instruction
+            .p4
+            .rsplit('(')
+            .next()
+            .and_then(|argc| argc.trim_end_matches(')').parse().ok())
+            .filter(|argc| *argc >= 0),
+
+        _ => Some(instruction.p5),
+    }
+}
+
+#[derive(Debug)]
+struct Instruction {
+    opcode: String,
+    p1: i64,
+    p2: i64,
+    p3: i64,
+    p4: String,
+    p5: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Register {
+    Single(Value),
+    Record(Vec<Value>),
+
+    // an integer set by the program itself, such as a flag deciding a branch
+    Integer(i64),
+
+    // the address of the instruction a subroutine or coroutine returns to
+    Address(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cursor {
+    Record(Vec<Value>),
+
+    // an ephemeral table, index or sorter that nothing was written to; it has no rows
+    Empty,
+
+    // a cursor over the single record held in a register
+    Pseudo(i64),
+}
+
+// what is known about the registers and cursors at some point of the program
+#[derive(Debug, Clone, Default, PartialEq)]
+struct State {
+    registers: HashMap<i64, Register>,
+    cursors: HashMap<i64, Cursor>,
+
+    // cursors that were moved onto a row of NULLs for an outer join
+    null_rows: HashSet<i64>,
+
+    // the `Once` instructions that were passed through
+    once: HashSet<usize>,
+}
+
+impl State {
+    fn value(&self, register: i64) -> Value {
+        match self.registers.get(&register) {
+            Some(Register::Single(value)) => *value,
+            Some(Register::Integer(_)) => Value::of(DataType::Int64),
+
+            _ => Value::UNKNOWN,
+        }
+    }
+
+    fn integer(&self, register: i64) -> Option<i64> {
+        match self.registers.get(&register) {
+            Some(Register::Integer(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn values(&self, register: i64, count: i64) -> Vec<Value> {
+        (register..register + count)
+            .map(|register| self.value(register))
+            .collect()
+    }
+
+    fn set(&mut self, register: i64, value: Value) {
+        self.registers.insert(register, Register::Single(value));
+    }
+
+    fn address(&self, register: i64) -> Option<usize> {
+        match self.registers.get(&register) {
+            Some(Register::Address(address)) => Some(*address),
+            _ => None,
+        }
+    }
+
+    // returns nothing if the cursor cannot be on a row
+    fn column(&self, cursor: i64, column: i64) -> Option<Value> {
+        if self.null_rows.contains(&cursor) {
+            return Some(Value::NULL);
+        }
+
+        let record = match self.cursors.get(&cursor) {
+            Some(Cursor::Record(record)) => record,
+            Some(Cursor::Empty) => return None,
+
+            Some(Cursor::Pseudo(register)) => match self.registers.get(register) {
+                Some(Register::Record(record)) => record,
+                _ => return Some(Value::UNKNOWN),
+            },
+
+            None => return Some(Value::UNKNOWN),
+        };
+
+        Some(
+            record
+                .get(column as usize)
+                .copied()
+                .unwrap_or(Value::UNKNOWN),
+        )
+    }
+
+    fn open(&mut self, cursor: i64, record: Option<Vec<Value>>) {
+        self.null_rows.remove(&cursor);
+
+        match record {
+            Some(record) => self.cursors.insert(cursor, Cursor::Record(record)),
+            None => self.cursors.remove(&cursor),
+        };
+    }
+}
+
+// Infers the type and nullability of the result columns of a statement by walking the
+// bytecode program SQLite compiled it to; every path through the program is followed, looking
+// at what each register may hold by the time it reaches a `ResultRow`.
+//
+// Returns nothing if the program is beyond the analysis.
+pub(super) async fn explain(
+    conn: &mut SqliteConnection,
+    sql: &str,
+) -> Result<Vec<(Option<SqliteTypeInfo>, Option<bool>)>, Error> {
+    // parameters are left unbound, the program does not depend on their values
+    let rows = conn.fetch_all(&*format!("EXPLAIN {}", sql)).await?;
+
+    let mut program = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        // P5 is listed as a hex string by older versions of SQLite and as an integer by newer ones
+        let p5 = match row.try_get_raw(6)?.type_info().as_deref() {
+            Some(SqliteTypeInfo(DataType::Text)) => {
+                i64::from_str_radix(&row.try_get::<String, _>(6)?, 16).unwrap_or_default()
+            }
+
+            _ => row.try_get(6)?,
+        };
+
+        program.push(Instruction {
+            opcode: row.try_get(1)?,
+            p1: row.try_get(2)?,
+            p2: row.try_get(3)?,
+            p3: row.try_get(4)?,
+            p4: row.try_get::<Option<String>, _>(5)?.unwrap_or_default(),
+            p5,
+        });
+    }
+
+    let mut tables = HashMap::new();
+
+    for instruction in &program {
+        if (instruction.opcode == OP_OPEN_READ || instruction.opcode == OP_OPEN_WRITE)
+            && instruction.p5 & OPFLAG_P2ISREG == 0
+        {
+            let key = (instruction.p3, instruction.p2);
+
+            if !tables.contains_key(&key) {
+                let record = root_page(conn, instruction.p3, instruction.p2).await?;
+
+                tables.insert(key, record);
+            }
+        }
+    }
+
+    Ok(analyze(&program, &tables)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|value| {
+            let ty = match value.ty {
+                ValueType::Known(ty) => Some(SqliteTypeInfo(ty)),
+                _ => None,
+            };
+
+            (ty, value.not_null)
+        })
+        .collect())
+}
+
+// the columns stored by the table or index at the given root page of a database
+async fn root_page(
+    conn: &mut SqliteConnection,
+    database: i64,
+    root_page: i64,
+) -> Result<Option<Vec<Value>>, Error> {
+    let (schema, master) = match database {
+        0 => ("main", "sqlite_master"),
+        1 => ("temp", "sqlite_temp_master"),
+
+        // attached databases are not looked into
+        _ => return Ok(None),
+    };
+
+    let object: Option<(String, String, String, Option<String>)> = query_as(&format!(
+        "SELECT type, name, tbl_name, sql FROM {} WHERE rootpage = ?",
+        master
+    ))
+    .bind(root_page)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let (kind, name, table, sql) = match object {
+        Some(object) => object,
+        None => return Ok(None),
+    };
+
+    let table_sql: Option<String> = if kind == "table" {
+        sql
+    } else {
+        query_scalar(&format!(
+            "SELECT sql FROM {} WHERE type = 'table' AND name = ?",
+            master
+        ))
+        .bind(&table)
+        .fetch_optional(&mut *conn)
+        .await?
+        .flatten()
+    };
+
+    // the records of a WITHOUT ROWID table are not laid out in the order of its columns
+    if let Some(sql) = table_sql {
+        let sql = sql.to_ascii_uppercase();
+        let sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if sql.contains("WITHOUT ROWID") {
+            return Ok(None);
+        }
+    }
+
+    let columns: Vec<(String, bool)> = query_as(
+        "SELECT type, \"notnull\" FROM pragma_table_info WHERE arg = ? AND schema = ? ORDER BY cid",
+    )
+    .bind(&table)
+    .bind(schema)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let columns: Vec<Value> = columns
+        .into_iter()
+        .map(|(decl, not_null)| Value {
+            ty: decl.parse().map_or(ValueType::Unknown, ValueType::Known),
+            not_null: Some(not_null),
+        })
+        .collect();
+
+    if kind == "table" {
+        return Ok(Some(columns));
+    }
+
+    // an index record holds the indexed columns followed by the rowid
+    let indexed: Vec<i64> = query_scalar(
+        "SELECT cid FROM pragma_index_info WHERE arg = ? AND schema = ? ORDER BY seqno",
+    )
+    .bind(&name)
+    .bind(schema)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut record: Vec<Value> = indexed
+        .into_iter()
+        .map(|cid| match cid {
+            -1 => Value::of(DataType::Int64),
+            cid => columns.get(cid as usize).copied().unwrap_or(Value::UNKNOWN),
+        })
+        .collect();
+
+    record.push(Value::of(DataType::Int64));
+
+    Ok(Some(record))
+}
+
+fn analyze(
+    program: &[Instruction],
+    tables: &HashMap<(i64, i64), Option<Vec<Value>>>,
+) -> Option<Vec<Value>> {
+    let mut result: Option<Vec<Value>> = None;
+
+    // the states each jump target was reached with; a path that reaches a jump target in a
+    // state it was reached with before has nothing new to tell
+    let mut visited: HashMap<usize, Vec<State>> = HashMap::new();
+
+    let mut branches = vec![(0, State::default(), false)];
+    let mut steps = 0;
+
+    while let Some((mut pc, mut state, mut jumped)) = branches.pop() {
+        // the register a following min() or max() flags rows that are not a new extreme in
+        let mut extreme_flag = None;
+
+        loop {
+            steps += 1;
+
+            if steps > MAX_STEPS {
+                return None;
+            }
+
+            if jumped {
+                let seen = visited.entry(pc).or_default();
+
+                if seen.contains(&state) {
+                    break;
+                }
+
+                seen.push(state.clone());
+                jumped = false;
+            }
+
+            let instruction = match program.get(pc) {
+                Some(instruction) => instruction,
+                None => break,
+            };
+
+            let Instruction { p1, p2, p3, p5, .. } = *instruction;
+
+            let mut next = pc + 1;
+
+            match &*instruction.opcode {
+                OP_INIT | OP_GOTO => {
+                    next = p2 as usize;
+                    jumped = true;
+                }
+
+                OP_GOSUB => {
+                    state.registers.insert(p1, Register::Address(pc));
+
+                    next = p2 as usize;
+                    jumped = true;
+                }
+
+                OP_RETURN => match state.address(p1) {
+                    Some(address) => {
+                        next = address + 1;
+                        jumped = true;
+                    }
+
+                    None => break,
+                },
+
+                OP_INIT_COROUTINE => {
+                    state
+                        .registers
+                        .insert(p1, Register::Address((p3 as usize).saturating_sub(1)));
+
+                    if p2 != 0 {
+                        next = p2 as usize;
+                        jumped = true;
+                    }
+                }
+
+                OP_YIELD => match state.address(p1) {
+                    Some(address) => {
+                        state.registers.insert(p1, Register::Address(pc));
+
+                        next = address + 1;
+                        jumped = true;
+                    }
+
+                    None => break,
+                },
+
+                OP_END_COROUTINE => match state.address(p1).and_then(|a| program.get(a)) {
+                    Some(caller) => {
+                        state.registers.remove(&p1);
+
+                        next = caller.p2 as usize;
+                        jumped = true;
+                    }
+
+                    None => break,
+                },
+
+                OP_JUMP => {
+                    for target in &[p1, p2] {
+                        branches.push((*target as usize, state.clone(), true));
+                    }
+
+                    next = p3 as usize;
+                    jumped = true;
+                }
+
+                OP_HALT => break,
+
+                OP_RESULT_ROW => {
+                    let row = state.values(p1, p2);
+
+                    result = Some(match result {
+                        Some(previous) if previous.len() == row.len() => previous
+                            .into_iter()
+                            .zip(row)
+                            .map(|(a, b)| a.merge(b))
+                            .collect(),
+
+                        _ => row,
+                    });
+                }
+
+                // comparisons store their result instead of jumping when asked to
+                OP_EQ | OP_NE | OP_LT | OP_LE | OP_GT | OP_GE if p5 & SQLITE_STOREP2 != 0 => {
+                    let value = if p5 & SQLITE_NULLEQ != 0 {
+                        Value::of(DataType::Int64)
+                    } else {
+                        typed(DataType::Int64, &[state.value(p1), state.value(p3)])
+                    };
+
+                    state.set(p2, value);
+                }
+
+                OP_NOT_NULL => {
+                    let mut taken = state.clone();
+                    let value = taken.value(p1);
+
+                    taken.set(
+                        p1,
+                        Value {
+                            not_null: Some(true),
+                            ..value
+                        },
+                    );
+
+                    branches.push((p2 as usize, taken, true));
+                }
+
+                OP_IS_NULL => {
+                    branches.push((p2 as usize, state.clone(), true));
+
+                    let value = state.value(p1);
+
+                    state.set(
+                        p1,
+                        Value {
+                            not_null: Some(true),
+                            ..value
+                        },
+                    );
+                }
+
+                OP_IF | OP_IF_NOT => {
+                    let jump = state
+                        .integer(p1)
+                        .map(|value| (value != 0) == (instruction.opcode == OP_IF));
+
+                    match jump {
+                        Some(true) => {
+                            next = p2 as usize;
+                            jumped = true;
+                        }
+
+                        Some(false) => {}
+
+                        None => branches.push((p2 as usize, state.clone(), true)),
+                    }
+                }
+
+                OP_IF_POS => match state.integer(p1) {
+                    Some(value) if value >= 1 => {
+                        state.registers.insert(p1, Register::Integer(value - p3));
+
+                        next = p2 as usize;
+                        jumped = true;
+                    }
+
+                    Some(_) => {}
+
+                    None => branches.push((p2 as usize, state.clone(), true)),
+                },
+
+                // falls through the first time only
+                OP_ONCE => {
+                    let first = state.once.insert(pc);
+
+                    if !first {
+                        next = p2 as usize;
+                        jumped = true;
+                    }
+                }
+
+                OP_IF_NULL_ROW => {
+                    let mut taken = state.clone();
+                    taken.set(p3, Value::NULL);
+
+                    branches.push((p2 as usize, taken, true));
+                }
+
+                OP_MUST_BE_INT if p2 == 0 => {}
+
+                OP_IF_NOT_ZERO | OP_IF_NOT_OPEN | OP_IF_NO_HOPE | OP_IF_SMALLER | OP_EQ | OP_NE
+                | OP_LT | OP_LE | OP_GT | OP_GE | OP_ELSE_EQ | OP_MUST_BE_INT | OP_REWIND
+                | OP_LAST | OP_NEXT | OP_PREV | OP_SORT | OP_SORTER_SORT | OP_SORTER_NEXT
+                | OP_SEEK_LT | OP_SEEK_LE | OP_SEEK_GE | OP_SEEK_GT | OP_SEEK_ROWID
+                | OP_NOT_EXISTS | OP_FOUND | OP_NOT_FOUND | OP_NO_CONFLICT | OP_IDX_LT
+                | OP_IDX_LE | OP_IDX_GT | OP_IDX_GE | OP_ROW_SET_READ | OP_ROW_SET_TEST
+                | OP_DECR_JUMP_ZERO | OP_FILTER | OP_VFILTER | OP_VNEXT => {
+                    branches.push((p2 as usize, state.clone(), true));
+                }
+
+                OP_OPEN_READ | OP_OPEN_WRITE => {
+                    let record = tables.get(&(p3, p2)).cloned().flatten();
+
+                    state.open(p1, record.filter(|_| p5 & OPFLAG_P2ISREG == 0));
+                }
+
+                OP_OPEN_DUP => {
+                    let record = match state.cursors.get(&p2) {
+                        Some(Cursor::Record(record)) => Some(record.clone()),
+                        _ => None,
+                    };
+
+                    state.open(p1, record);
+                }
+
+                OP_OPEN_EPHEMERAL | OP_OPEN_AUTOINDEX | OP_SORTER_OPEN => {
+                    state.null_rows.remove(&p1);
+                    state.cursors.insert(p1, Cursor::Empty);
+                }
+
+                OP_OPEN_PSEUDO => {
+                    state.null_rows.remove(&p1);
+                    state.cursors.insert(p1, Cursor::Pseudo(p2));
+                }
+
+                OP_NULL_ROW => {
+                    state.null_rows.insert(p1);
+                }
+
+                OP_COLUMN => match state.column(p1, p2) {
+                    Some(value) => state.set(p3, value),
+                    None => break,
+                },
+
+                OP_ROWID => {
+                    let value = if state.null_rows.contains(&p1) {
+                        Value::NULL
+                    } else {
+                        Value::of(DataType::Int64)
+                    };
+
+                    state.set(p2, value);
+                }
+
+                OP_COUNT => {
+                    state.set(p2, Value::of(DataType::Int64));
+                }
+
+                OP_MAKE_RECORD => {
+                    let record = state.values(p1, p2);
+                    state.registers.insert(p3, Register::Record(record));
+                }
+
+                OP_INSERT | OP_IDX_INSERT | OP_SORTER_INSERT => {
+                    let record = match state.registers.get(&p2) {
+                        Some(Register::Record(record)) => record,
+
+                        _ => {
+                            state.cursors.remove(&p1);
+                            pc = next;
+
+                            continue;
+                        }
+                    };
+
+                    // a cursor written from several places holds any of their records
+                    let record = match state.cursors.get(&p1) {
+                        Some(Cursor::Record(previous)) if previous.len() == record.len() => {
+                            previous
+                                .iter()
+                                .zip(record)
+                                .map(|(a, b)| a.merge(*b))
+                                .collect()
+                        }
+
+                        _ => record.clone(),
+                    };
+
+                    state.cursors.insert(p1, Cursor::Record(record));
+                }
+
+                OP_SORTER_DATA => {
+                    match state.cursors.get(&p1) {
+                        Some(Cursor::Record(record)) => {
+                            let record = record.clone();
+                            state.registers.insert(p2, Register::Record(record));
+                        }
+
+                        Some(Cursor::Empty) => break,
+
+                        _ => {
+                            state.registers.remove(&p2);
+                        }
+                    };
+                }
+
+                OP_NULL => {
+                    for register in p2..=p2.max(p3) {
+                        state.set(register, Value::NULL);
+                    }
+                }
+
+                OP_SOFT_NULL => {
+                    state.set(p1, Value::NULL);
+                }
+
+                OP_INTEGER => {
+                    state.registers.insert(p2, Register::Integer(p1));
+                }
+
+                OP_INT64 => {
+                    state.set(p2, Value::of(DataType::Int64));
+                }
+
+                OP_REAL => {
+                    state.set(p2, Value::of(DataType::Float));
+                }
+
+                OP_STRING | OP_STRING8 => {
+                    state.set(p2, Value::of(DataType::Text));
+                }
+
+                OP_BLOB => {
+                    state.set(p2, Value::of(DataType::Blob));
+                }
+
+                OP_VARIABLE => {
+                    state.set(p2, Value::UNKNOWN);
+                }
+
+                OP_MOVE => {
+                    for i in 0..p3 {
+                        let register = state
+                            .registers
+                            .insert(p1 + i, Register::Single(Value::NULL))
+                            .unwrap_or(Register::Single(Value::UNKNOWN));
+
+                        state.registers.insert(p2 + i, register);
+                    }
+                }
+
+                OP_COPY | OP_SCOPY => {
+                    let count = if instruction.opcode == OP_COPY {
+                        p3 + 1
+                    } else {
+                        1
+                    };
+
+                    for i in 0..count {
+                        match state.registers.get(&(p1 + i)).cloned() {
+                            Some(register) => state.registers.insert(p2 + i, register),
+                            None => state.registers.remove(&(p2 + i)),
+                        };
+                    }
+                }
+
+                OP_INT_COPY => {
+                    let value = state.value(p1);
+                    state.set(p2, typed(DataType::Int64, &[value]));
+                }
+
+                OP_CAST => {
+                    let value = state.value(p1);
+
+                    let value = match p2 {
+                        SQLITE_AFF_BLOB => typed(DataType::Blob, &[value]),
+                        SQLITE_AFF_TEXT => typed(DataType::Text, &[value]),
+                        SQLITE_AFF_INTEGER => typed(DataType::Int64, &[value]),
+                        SQLITE_AFF_REAL => typed(DataType::Float, &[value]),
+
+                        // NUMERIC may end up as either an integer or a real
+                        _ => Value {
+                            ty: ValueType::Unknown,
+                            ..value
+                        },
+                    };
+
+                    state.set(p1, value);
+                }
+
+                OP_ADD | OP_SUBTRACT | OP_MULTIPLY => {
+                    let value = arithmetic(state.value(p1), state.value(p2));
+                    state.set(p3, value);
+                }
+
+                // dividing by zero results in NULL
+                OP_DIVIDE | OP_REMAINDER => {
+                    let value = arithmetic(state.value(p1), state.value(p2));
+
+                    state.set(
+                        p3,
+                        Value {
+                            not_null: value.not_null.filter(|not_null| !not_null),
+                            ..value
+                        },
+                    );
+                }
+
+                OP_CONCAT => {
+                    let value = typed(DataType::Text, &[state.value(p1), state.value(p2)]);
+                    state.set(p3, value);
+                }
+
+                OP_BIT_AND | OP_BIT_OR | OP_SHIFT_LEFT | OP_SHIFT_RIGHT => {
+                    let value = typed(DataType::Int64, &[state.value(p1), state.value(p2)]);
+                    state.set(p3, value);
+                }
+
+                OP_BIT_NOT => {
+                    let value = typed(DataType::Int64, &[state.value(p1)]);
+                    state.set(p2, value);
+                }
+
+                OP_NOT => {
+                    let value = typed(DataType::Bool, &[state.value(p1)]);
+                    state.set(p2, value);
+                }
+
+                OP_AND | OP_OR => {
+                    let value = typed(DataType::Bool, &[state.value(p1), state.value(p2)]);
+                    state.set(p3, value);
+                }
+
+                OP_IS_TRUE => {
+                    state.set(p2, Value::of(DataType::Bool));
+                }
+
+                OP_ZERO_OR_NULL => {
+                    let value = typed(DataType::Int64, &[state.value(p1), state.value(p3)]);
+                    state.set(p2, value);
+                }
+
+                OP_FUNCTION | OP_FUNCTION0 | OP_PURE_FUNC | OP_PURE_FUNC0 => {
+                    let value = match function_argc(instruction) {
+                        Some(argc) => {
+                            function(&function_name(&instruction.p4), &state.values(p2, argc))
+                        }
+
+                        None => Value::UNKNOWN,
+                    };
+
+                    state.set(p3, value);
+                }
+
+                OP_COLL_SEQ if p1 != 0 => {
+                    state.registers.insert(p1, Register::Integer(0));
+                    extreme_flag = Some(p1);
+                }
+
+                OP_AGG_STEP | OP_AGG_STEP0 | OP_AGG_STEP1 => {
+                    // the first row aggregated is always a new extreme
+                    if let Some(flag) = extreme_flag.take() {
+                        if state.value(p3).ty != ValueType::Null {
+                            state.registers.remove(&flag);
+                        }
+                    }
+
+                    // remember what is being aggregated in place of the accumulator
+                    let value = if p5 > 0 {
+                        state.value(p2)
+                    } else {
+                        Value::UNKNOWN
+                    };
+
+                    let accumulator = match state.value(p3) {
+                        Value {
+                            ty: ValueType::Null,
+                            ..
+                        } => value,
+
+                        accumulator => accumulator.merge(value),
+                    };
+
+                    state.set(p3, accumulator);
+                }
+
+                OP_AGG_FINAL => {
+                    let value = aggregate(&function_name(&instruction.p4), state.value(p1));
+                    state.set(p1, value);
+                }
+
+                OP_TRANSACTION | OP_TABLE_LOCK | OP_NOOP | OP_EXPLAIN | OP_TRACE | OP_CLOSE
+                | OP_AFFINITY | OP_COLL_SEQ | OP_COLUMNS_USED | OP_CURSOR_HINT | OP_SEEK_HIT
+                | OP_DEFERRED_SEEK | OP_FINISH_SEEK | OP_PERMUTATION | OP_COMPARE
+                | OP_RELEASE_REG => {}
+
+                _ => {
+                    // an instruction we know nothing about may have written to any register
+                    // among its operands; whether those can be NULL is no longer known
+                    for register in &[p1, p2, p3] {
+                        if let Some(Register::Single(value)) = state.registers.get_mut(register) {
+                            value.not_null = None;
+                        }
+                    }
+                }
+            }
+
+            pc = next;
+        }
+    }
+
+    result
+}
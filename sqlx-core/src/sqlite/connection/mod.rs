@@ -1,4 +1,5 @@
 use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use futures_core::future::BoxFuture;
@@ -7,21 +8,33 @@ use hashbrown::HashMap;
 
 use crate::connection::{Connect, Connection};
 use crate::error::Error;
+use crate::executor::Executor;
 use crate::ext::ustr::UStr;
-use crate::sqlite::connection::establish::establish;
-use crate::sqlite::statement::{SqliteStatement, StatementWorker};
-use crate::sqlite::{Sqlite, SqliteConnectOptions};
+use crate::sqlite::hooks::Hooks;
+use crate::sqlite::statement::SqliteStatement;
+use crate::sqlite::{
+    Sqlite, SqliteBackup, SqliteBackupTarget, SqliteConnectOptions, SqliteFunction,
+};
 
 mod establish;
 mod executor;
+mod explain;
 mod handle;
+mod worker;
 
 pub(crate) use handle::ConnectionHandle;
+pub(crate) use worker::{CommandSender, ConnectionWorker};
 
 /// A connection to a [Sqlite] database.
+///
+/// All calls into SQLite for a connection are run on a dedicated thread.
 pub struct SqliteConnection {
+    pub(crate) worker: ConnectionWorker,
+}
+
+// the state of a connection, which is owned by its worker thread
+pub(crate) struct ConnectionState {
     pub(crate) handle: ConnectionHandle,
-    pub(crate) worker: StatementWorker,
 
     // cache of semi-persistent statements
     pub(crate) statements: HashMap<String, SqliteStatement>,
@@ -30,7 +43,51 @@ pub struct SqliteConnection {
     pub(crate) statement: Option<SqliteStatement>,
 
     // working memory for the active row's column information
-    scratch_row_column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) scratch_row_column_names: Arc<HashMap<UStr, usize>>,
+
+    // callbacks set with [set_update_hook] and friends; dropped after the handle is closed
+    pub(crate) hooks: Hooks,
+
+    // read by the progress handler of the connection; dropped after the handle is closed
+    pub(crate) interrupt: Arc<AtomicBool>,
+}
+
+impl SqliteConnection {
+    /// Registers an application-defined SQL function on this connection.
+    ///
+    /// To register a function on every connection of a pool, use
+    /// [`SqliteConnectOptions::function`] instead.
+    pub async fn create_function(&mut self, function: &SqliteFunction) -> Result<(), Error> {
+        let function = function.clone();
+
+        self.worker
+            .run(move |conn| function.register(conn.handle.as_ptr()))
+            .await
+    }
+
+    /// Starts an [online backup](https://www.sqlite.org/backup.html) of the main database of
+    /// this connection to another connection or to a database file.
+    ///
+    /// The backup copies the database a number of pages at a time and only runs when the
+    /// returned [`SqliteBackup`] is awaited.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # async fn example(conn: &mut SqliteConnection) -> Result<(), Error> {
+    /// conn.backup_to("backup.db")
+    ///     .pages_per_step(64)
+    ///     .on_progress(|p| println!("{} of {} pages left", p.remaining(), p.page_count()))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn backup_to<'c, T>(&'c mut self, target: T) -> SqliteBackup<'c>
+    where
+        T: Into<SqliteBackupTarget<'c>>,
+    {
+        SqliteBackup::new(self, target.into())
+    }
 }
 
 impl Debug for SqliteConnection {
@@ -74,23 +131,27 @@ impl Connect for SqliteConnection {
     #[inline]
     fn connect_with(options: &Self::Options) -> BoxFuture<'_, Result<Self, Error>> {
         Box::pin(async move {
-            let conn = establish(options).await?;
+            let mut conn = SqliteConnection {
+                worker: ConnectionWorker::establish(options).await?,
+            };
 
-            // TODO: Apply any connection options once we have them defined
+            // send the PRAGMAs for the options that were set
+            let pragmas = options.pragmas();
+
+            if !pragmas.is_empty() {
+                conn.execute(&*pragmas).await?;
+            }
 
             Ok(conn)
         })
     }
 }
 
-impl Drop for SqliteConnection {
+impl Drop for ConnectionState {
     fn drop(&mut self) {
         // before the connection handle is dropped,
         // we must explicitly drop the statements as the drop-order in a struct is undefined
         self.statements.clear();
         self.statement.take();
-
-        // we then explicitly close the worker
-        self.worker.close();
     }
 }
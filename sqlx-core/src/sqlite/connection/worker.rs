@@ -0,0 +1,460 @@
+use std::future::Future;
+use std::os::raw::{c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use either::Either;
+use futures_channel::oneshot;
+use hashbrown::HashMap;
+use libsqlite3_sys::{sqlite3_progress_handler, SQLITE_DONE, SQLITE_ROW};
+
+use crate::error::Error;
+use crate::sqlite::connection::establish::establish;
+use crate::sqlite::connection::{ConnectionHandle, ConnectionState};
+use crate::sqlite::statement::{SqliteStatement, StatementHandle};
+use crate::sqlite::{SqliteArguments, SqliteConnectOptions, SqliteRow};
+
+// Every SQLite connection has a dedicated thread that opens the database, owns the
+// connection handle and runs all calls into SQLite, one command at a time. The async side
+// only ever sends commands and waits for their replies, so no call into SQLite ever blocks
+// the runtime and the connection behaves the same on every runtime.
+
+// the number of virtual machine instructions between checks for an interrupted statement
+const PROGRESS_INTERVAL: c_int = 1000;
+
+pub(crate) struct ConnectionWorker {
+    command_tx: Sender<Command>,
+
+    // set when the rows of a statement are no longer wanted; read by the progress handler
+    // of the connection and cleared before each command
+    interrupt: Arc<AtomicBool>,
+
+    thread: Option<JoinHandle<()>>,
+}
+
+enum Command {
+    Execute {
+        query: Box<str>,
+        arguments: Option<SqliteArguments<'static>>,
+        persistent: bool,
+        requests: Receiver<RowReply>,
+    },
+
+    Run(Box<dyn FnOnce(&mut ConnectionState) + Send>),
+
+    Shutdown,
+}
+
+// where to send the next result of an executing statement; [None] once there are no more
+type RowReply = oneshot::Sender<Result<Option<Either<u64, SqliteRow>>, Error>>;
+
+impl ConnectionWorker {
+    pub(crate) async fn establish(options: &SqliteConnectOptions) -> Result<Self, Error> {
+        let options = options.clone();
+        let interrupt = Arc::new(AtomicBool::new(false));
+
+        let (command_tx, command_rx) = unbounded();
+        let (established_tx, established_rx) = oneshot::channel();
+
+        let thread = thread::Builder::new()
+            .name("sqlx-sqlite-worker".into())
+            .spawn({
+                let interrupt = Arc::clone(&interrupt);
+
+                move || {
+                    let mut conn = match establish(&options, interrupt) {
+                        Ok(conn) => conn,
+                        Err(error) => {
+                            let _ = established_tx.send(Err(error));
+                            return;
+                        }
+                    };
+
+                    // unlike [sqlite3_interrupt], this only ever stops the step in progress;
+                    // the former also fails statements started while any other is active
+                    // <https://www.sqlite.org/c3ref/progress_handler.html>
+                    unsafe {
+                        sqlite3_progress_handler(
+                            conn.handle.as_ptr(),
+                            PROGRESS_INTERVAL,
+                            Some(progress),
+                            &*conn.interrupt as *const AtomicBool as *mut c_void,
+                        );
+                    }
+
+                    if established_tx.send(Ok(())).is_err() {
+                        // the connection is not wanted anymore
+                        return;
+                    }
+
+                    run(&mut conn, command_rx);
+                }
+            })?;
+
+        let worker = Self {
+            command_tx,
+            interrupt,
+            thread: Some(thread),
+        };
+
+        established_rx.await.map_err(|_| Error::WorkerCrashed)??;
+
+        Ok(worker)
+    }
+
+    /// Runs `f` with the connection on the worker thread.
+    ///
+    /// The returned future does not borrow the worker. Once sent, the command runs to
+    /// completion even if the future is dropped.
+    pub(crate) fn run<F, R>(&self, f: F) -> impl Future<Output = Result<R, Error>> + Send + 'static
+    where
+        F: FnOnce(&mut ConnectionState) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+
+        let sent = self.send(Command::Run(Box::new(move |conn| {
+            let _ = tx.send(f(conn));
+        })));
+
+        async move {
+            sent?;
+
+            rx.await.map_err(|_| Error::WorkerCrashed)?
+        }
+    }
+
+    /// Runs `f` with the connection on the worker thread, without waiting for it.
+    ///
+    /// Commands run in the order they were sent so `f` is done before any later command.
+    pub(crate) fn run_detached<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut ConnectionState) + Send + 'static,
+    {
+        self.send(Command::Run(Box::new(f)))
+    }
+
+    /// Runs `f` with the connection on the worker thread, along with the connection of
+    /// `other`, for calls into SQLite that use both.
+    ///
+    /// The worker of `other` is held in a command of its own until `f` is done, so neither
+    /// connection is used by two threads at the same time.
+    pub(crate) fn run_with<F, R>(
+        &self,
+        other: &ConnectionWorker,
+        f: F,
+    ) -> impl Future<Output = Result<R, Error>> + Send + 'static
+    where
+        F: FnOnce(&mut ConnectionState, &mut ConnectionState) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (held_tx, held_rx) = crossbeam_channel::bounded(1);
+        let (release_tx, release_rx) = crossbeam_channel::bounded::<()>(0);
+
+        let held = other.send(Command::Run(Box::new(move |conn| {
+            if held_tx.send(StatePtr(conn)).is_ok() {
+                // returns once the sender is dropped, along with the command below
+                let _ = release_rx.recv();
+            }
+        })));
+
+        let result = self.run(move |conn| {
+            let _release = release_tx;
+
+            let other = held_rx.recv().map_err(|_| Error::WorkerCrashed)?;
+
+            // SAFE: the worker of the other connection does nothing until [_release] is dropped
+            f(conn, unsafe { &mut *other.0 })
+        });
+
+        async move {
+            held?;
+            result.await
+        }
+    }
+
+    /// Prepares (or checks out from the cache) and binds `query` on the worker thread and
+    /// returns a handle to step through its results.
+    pub(crate) fn execute(
+        &self,
+        query: &str,
+        arguments: Option<SqliteArguments<'_>>,
+        persistent: bool,
+    ) -> Result<Execution, Error> {
+        let (requests_tx, requests_rx) = unbounded();
+
+        self.send(Command::Execute {
+            query: query.into(),
+            arguments: arguments.map(SqliteArguments::into_static),
+            persistent,
+            requests: requests_rx,
+        })?;
+
+        Ok(Execution {
+            requests: requests_tx,
+            interrupt: Arc::clone(&self.interrupt),
+            done: false,
+        })
+    }
+
+    /// Returns a sender of commands to the worker that does not borrow the connection.
+    pub(crate) fn sender(&self) -> CommandSender {
+        CommandSender(self.command_tx.clone())
+    }
+
+    fn send(&self, command: Command) -> Result<(), Error> {
+        self.command_tx
+            .send(command)
+            .map_err(|_| Error::WorkerCrashed)
+    }
+}
+
+// for values that call into SQLite when they are dropped, which may be after the borrow of
+// their connection has ended
+#[derive(Clone)]
+pub(crate) struct CommandSender(Sender<Command>);
+
+impl CommandSender {
+    /// Runs `f` with the connection on the worker thread, without waiting for it.
+    pub(crate) fn run_detached<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut ConnectionState) + Send + 'static,
+    {
+        self.0
+            .send(Command::Run(Box::new(f)))
+            .map_err(|_| Error::WorkerCrashed)
+    }
+}
+
+impl Drop for ConnectionWorker {
+    fn drop(&mut self) {
+        // stop a statement that is still running so the thread does not have to finish it
+        self.interrupt.store(true, Ordering::Release);
+
+        // any command sent before, such as the rollback of a dropped transaction, still runs
+        let _ = self.command_tx.send(Command::Shutdown);
+
+        if let Some(thread) = self.thread.take() {
+            // NOTE: ignore a panic of the worker; there is nothing left to report it to
+            let _ = thread.join();
+        }
+    }
+}
+
+// the connection of a worker that is held by a command of [run_with]
+struct StatePtr(*mut ConnectionState);
+
+unsafe impl Send for StatePtr {}
+
+/// The results of a statement that is executing on the worker thread, requested one at a
+/// time.
+///
+/// If this is dropped before all results are read, a step that is in progress is
+/// interrupted and the statement is reset.
+pub(crate) struct Execution {
+    requests: Sender<RowReply>,
+    interrupt: Arc<AtomicBool>,
+    done: bool,
+}
+
+impl Execution {
+    /// Returns the next row or the number of rows changed by one of the statements of the
+    /// query; [None] once there are no more.
+    pub(crate) async fn next(&mut self) -> Result<Option<Either<u64, SqliteRow>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let (tx, rx) = oneshot::channel();
+
+        self.requests.send(tx).map_err(|_| Error::WorkerCrashed)?;
+
+        let result = rx.await.map_err(|_| Error::WorkerCrashed)?;
+
+        // the worker moves on to the next command after an error or the last result
+        self.done = !matches!(result, Ok(Some(_)));
+
+        result
+    }
+}
+
+impl Drop for Execution {
+    fn drop(&mut self) {
+        if !self.done {
+            self.interrupt.store(true, Ordering::Release);
+        }
+    }
+}
+
+fn run(conn: &mut ConnectionState, commands: Receiver<Command>) {
+    for command in commands {
+        // no statement of an earlier command can still be running; an interrupt that was
+        // requested for one must not affect this command
+        conn.interrupt.store(false, Ordering::Release);
+
+        match command {
+            Command::Execute {
+                query,
+                arguments,
+                persistent,
+                requests,
+            } => {
+                execute(conn, &query, arguments, persistent, requests);
+            }
+
+            Command::Run(f) => f(conn),
+
+            Command::Shutdown => break,
+        }
+    }
+}
+
+fn execute(
+    conn: &mut ConnectionState,
+    query: &str,
+    arguments: Option<SqliteArguments<'_>>,
+    persistent: bool,
+    requests: Receiver<RowReply>,
+) {
+    let ConnectionState {
+        handle,
+        statements,
+        statement,
+        scratch_row_column_names,
+        ..
+    } = conn;
+
+    // prepare statement object (or checkout from cache) and bind arguments, if any
+    let statement =
+        match prepare(handle, statements, statement, query, persistent).and_then(|statement| {
+            if let Some(arguments) = arguments {
+                arguments.bind(&*statement)?;
+            }
+
+            Ok(statement)
+        }) {
+            Ok(statement) => statement,
+
+            Err(error) => {
+                // an error is the reply to the first request
+                if let Ok(reply) = requests.recv() {
+                    let _ = reply.send(Err(error));
+                }
+
+                return;
+            }
+        };
+
+    loop {
+        let handle = match statement.execute() {
+            Ok(Some(handle)) => handle,
+            Ok(None) => break,
+
+            Err(error) => {
+                if let Ok(reply) = requests.recv() {
+                    let _ = reply.send(Err(error));
+                }
+
+                return;
+            }
+        };
+
+        let column_names = Arc::make_mut(scratch_row_column_names);
+        column_names.clear();
+
+        for i in 0..handle.column_count() {
+            column_names.insert(handle.column_name(i).to_owned().into(), i);
+        }
+
+        loop {
+            // wait for the receiving end to ask for the next row; if it is gone, the
+            // statement is reset so it does not hold on to any locks
+            let reply = match requests.recv() {
+                Ok(reply) => reply,
+
+                Err(_) => {
+                    handle.reset();
+                    return;
+                }
+            };
+
+            let (result, done) = match step(handle) {
+                Ok(Either::Right(())) => (
+                    Ok(Some(Either::Right(SqliteRow::current(
+                        handle,
+                        scratch_row_column_names,
+                    )))),
+                    false,
+                ),
+
+                Ok(Either::Left(changes)) => (Ok(Some(Either::Left(changes))), true),
+
+                Err(error) => {
+                    handle.reset();
+
+                    let _ = reply.send(Err(error));
+
+                    return;
+                }
+            };
+
+            if reply.send(result).is_err() {
+                handle.reset();
+                return;
+            }
+
+            if done {
+                break;
+            }
+        }
+    }
+
+    if let Ok(reply) = requests.recv() {
+        let _ = reply.send(Ok(None));
+    }
+}
+
+fn prepare<'a>(
+    conn: &mut ConnectionHandle,
+    statements: &'a mut HashMap<String, SqliteStatement>,
+    statement: &'a mut Option<SqliteStatement>,
+    query: &str,
+    persistent: bool,
+) -> Result<&'a mut SqliteStatement, Error> {
+    if !persistent {
+        *statement = Some(SqliteStatement::prepare(conn, query, false)?);
+        return Ok(statement.as_mut().unwrap());
+    }
+
+    if !statements.contains_key(query) {
+        let statement = SqliteStatement::prepare(conn, query, false)?;
+        statements.insert(query.to_owned(), statement);
+    }
+
+    let statement = statements.get_mut(query).unwrap();
+
+    // as this statement has been executed before, we reset before continuing
+    statement.reset();
+
+    Ok(statement)
+}
+
+fn step(statement: &StatementHandle) -> Result<Either<u64, ()>, Error> {
+    match statement.step() {
+        // a row was found
+        SQLITE_ROW => Ok(Either::Right(())),
+
+        // reached the end of the query results,
+        // emit the # of changes
+        SQLITE_DONE => Ok(Either::Left(statement.changes())),
+
+        _ => Err(statement.last_error().into()),
+    }
+}
+
+// a non-zero return fails the step in progress with SQLITE_INTERRUPT
+unsafe extern "C" fn progress(interrupt: *mut c_void) -> c_int {
+    (*(interrupt as *const AtomicBool)).load(Ordering::Acquire) as c_int
+}
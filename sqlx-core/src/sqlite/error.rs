@@ -35,6 +35,11 @@ impl SqliteError {
             message: message.to_owned(),
         }
     }
+
+    // for the few calls that return their error message rather than leave it on the connection
+    pub(crate) fn with_message(code: c_int, message: String) -> Self {
+        Self { code, message }
+    }
 }
 
 impl Display for SqliteError {
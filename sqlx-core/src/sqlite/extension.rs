@@ -0,0 +1,98 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use std::ptr::{null, null_mut};
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_db_config, sqlite3_free, sqlite3_load_extension,
+    SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION, SQLITE_OK,
+};
+
+use crate::error::Error;
+use crate::sqlite::SqliteError;
+
+// a run-time loadable extension, registered with [SqliteConnectOptions::extension]
+#[derive(Debug, Clone)]
+pub(crate) struct Extension {
+    path: PathBuf,
+    entry_point: Option<String>,
+}
+
+impl Extension {
+    pub(crate) fn new(path: &Path, entry_point: Option<&str>) -> Self {
+        Self {
+            path: path.to_owned(),
+            entry_point: entry_point.map(ToOwned::to_owned),
+        }
+    }
+
+    pub(crate) fn load(&self, handle: *mut sqlite3) -> Result<(), Error> {
+        let path = self
+            .path
+            .to_str()
+            .and_then(|path| CString::new(path).ok())
+            .ok_or_else(|| err_protocol!("invalid extension path: {:?}", self.path))?;
+
+        let entry_point = self
+            .entry_point
+            .as_deref()
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| err_protocol!("invalid extension entry point: {:?}", self.entry_point))?;
+
+        // only the C API is enabled and only for as long as the extension is loaded, so SQL
+        // can never call the `load_extension()` function
+        // <https://www.sqlite.org/c3ref/c_dbconfig_defensive.html#sqlitedbconfigenableloadextension>
+        enable_load_extension(handle, true)?;
+
+        let mut message = null_mut();
+
+        // <https://www.sqlite.org/c3ref/load_extension.html>
+        let status = unsafe {
+            sqlite3_load_extension(
+                handle,
+                path.as_ptr(),
+                entry_point.as_ref().map_or(null(), |entry| entry.as_ptr()),
+                &mut message,
+            )
+        };
+
+        enable_load_extension(handle, false)?;
+
+        if status != SQLITE_OK {
+            // the message is not left on the connection, it is returned to us instead
+            let message = if message.is_null() {
+                format!("unable to load extension {:?}", self.path)
+            } else {
+                let text = unsafe { CStr::from_ptr(message) }
+                    .to_string_lossy()
+                    .into_owned();
+
+                unsafe { sqlite3_free(message as *mut _) };
+
+                text
+            };
+
+            return Err(SqliteError::with_message(status, message).into());
+        }
+
+        Ok(())
+    }
+}
+
+fn enable_load_extension(handle: *mut sqlite3, enable: bool) -> Result<(), Error> {
+    let status = unsafe {
+        sqlite3_db_config(
+            handle,
+            SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION,
+            enable as c_int,
+            null_mut::<c_int>(),
+        )
+    };
+
+    if status != SQLITE_OK {
+        return Err(SqliteError::new(handle).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,521 @@
+use std::any::Any;
+use std::ffi::CString;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+use std::sync::Arc;
+use std::thread;
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_aggregate_context, sqlite3_context, sqlite3_create_function_v2,
+    sqlite3_create_window_function, sqlite3_result_blob64, sqlite3_result_double,
+    sqlite3_result_error, sqlite3_result_error_nomem, sqlite3_result_int, sqlite3_result_int64,
+    sqlite3_result_null, sqlite3_result_text64, sqlite3_user_data, sqlite3_value,
+    SQLITE_DETERMINISTIC, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::{BoxDynError, Error};
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteError, SqliteValueRef};
+
+/// An application-defined SQL function, written as a Rust closure.
+///
+/// Functions are registered with [`SqliteConnectOptions::function`] so that every new
+/// connection has them (including every connection of a pool), or with
+/// [`SqliteConnection::create_function`] for a single connection.
+///
+/// The arguments of the closure are decoded from the SQL values passed to the function and
+/// the return value is encoded as its result; any type that can be bound to or read from a
+/// query can be used. Arguments must be owned types, e.g. `String` rather than `&str`.
+///
+/// # Example
+///
+/// ```rust
+/// # use sqlx_core::sqlite::{SqliteConnectOptions, SqliteFunction};
+/// let options = SqliteConnectOptions::new()
+///     .function(SqliteFunction::scalar("slugify", |title: String| {
+///         title.to_lowercase().replace(' ', "-")
+///     }).deterministic(true))
+///     .function(SqliteFunction::aggregate(
+///         "product",
+///         1.0,
+///         |product: &mut f64, value: f64| *product *= value,
+///         |product| product,
+///     ));
+/// ```
+///
+/// [`SqliteConnectOptions::function`]: crate::sqlite::SqliteConnectOptions::function
+/// [`SqliteConnection::create_function`]: crate::sqlite::SqliteConnection::create_function
+#[derive(Clone)]
+pub struct SqliteFunction {
+    name: String,
+    num_args: c_int,
+    deterministic: bool,
+    kind: FunctionKind,
+}
+
+#[derive(Clone)]
+enum FunctionKind {
+    Scalar(Arc<ScalarFn>),
+    Aggregate(Arc<Aggregate>),
+}
+
+type ScalarFn = dyn Fn(&[SqliteValueRef<'_>]) -> Result<SqliteArgumentValue<'static>, BoxDynError>
+    + Send
+    + Sync;
+
+type StepFn = dyn Fn(&mut State, &[SqliteValueRef<'_>]) -> Result<(), BoxDynError> + Send + Sync;
+
+// the state of an aggregate, with its concrete type erased
+type State = Box<dyn Any + Send>;
+
+struct Aggregate {
+    init: Box<dyn Fn() -> State + Send + Sync>,
+    step: Box<StepFn>,
+    finalize: Box<dyn Fn(State) -> SqliteArgumentValue<'static> + Send + Sync>,
+
+    // only set for window functions
+    inverse: Option<Box<StepFn>>,
+    value: Option<Box<dyn Fn(&State) -> SqliteArgumentValue<'static> + Send + Sync>>,
+}
+
+impl SqliteFunction {
+    /// Creates a scalar function, which computes one result for each call.
+    pub fn scalar<F, A, R>(name: &str, function: F) -> Self
+    where
+        F: ScalarFunction<A, R>,
+        A: FunctionArgs,
+        R: Encode<'static, Sqlite>,
+    {
+        Self {
+            name: name.to_owned(),
+            num_args: A::ARITY,
+            deterministic: false,
+            kind: FunctionKind::Scalar(Arc::new(move |values| {
+                Ok(encode_result(function.call(A::decode(values)?)))
+            })),
+        }
+    }
+
+    /// Creates an aggregate function, which computes one result from the values of a
+    /// group of rows.
+    ///
+    /// Each group starts with a copy of `init`; `step` is called for each row in the group and
+    /// `finalize` turns the state into the result.
+    pub fn aggregate<S, A, R, Step, Final>(name: &str, init: S, step: Step, finalize: Final) -> Self
+    where
+        S: Clone + Send + Sync + 'static,
+        A: FunctionArgs,
+        R: Encode<'static, Sqlite>,
+        Step: StepFunction<S, A>,
+        Final: Fn(S) -> R + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_owned(),
+            num_args: A::ARITY,
+            deterministic: false,
+            kind: FunctionKind::Aggregate(Arc::new(Aggregate {
+                init: Box::new(move || Box::new(init.clone())),
+                step: erase_step(step),
+                finalize: Box::new(move |state| encode_result(finalize(downcast(state)))),
+                inverse: None,
+                value: None,
+            })),
+        }
+    }
+
+    /// Creates an aggregate window function, which can also be used with an `OVER` clause.
+    ///
+    /// In addition to what [`aggregate`](Self::aggregate) does, `inverse` is called for each
+    /// row that leaves the window frame and `value` computes the result for the current
+    /// frame.
+    pub fn window<S, A, R, Step, Inverse, Value>(
+        name: &str,
+        init: S,
+        step: Step,
+        inverse: Inverse,
+        value: Value,
+    ) -> Self
+    where
+        S: Clone + Send + Sync + 'static,
+        A: FunctionArgs,
+        R: Encode<'static, Sqlite>,
+        Step: StepFunction<S, A>,
+        Inverse: StepFunction<S, A>,
+        Value: Fn(&S) -> R + Send + Sync + 'static,
+    {
+        let value = Arc::new(value);
+        let finalize = Arc::clone(&value);
+
+        Self {
+            name: name.to_owned(),
+            num_args: A::ARITY,
+            deterministic: false,
+            kind: FunctionKind::Aggregate(Arc::new(Aggregate {
+                init: Box::new(move || Box::new(init.clone())),
+                step: erase_step(step),
+                finalize: Box::new(move |state| encode_result(finalize(&downcast::<S>(state)))),
+                inverse: Some(erase_step(inverse)),
+                value: Some(Box::new(move |state| {
+                    encode_result(value(state.downcast_ref().expect("aggregate state type")))
+                })),
+            })),
+        }
+    }
+
+    /// Sets whether the function always gives the same result for the same arguments.
+    ///
+    /// This allows SQLite to optimize calls to the function and to use it in the
+    /// expressions of indexes and generated columns.
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    pub(crate) fn register(&self, handle: *mut sqlite3) -> Result<(), Error> {
+        let name = CString::new(&*self.name)
+            .map_err(|_| err_protocol!("function name {:?} contains a NUL byte", self.name))?;
+
+        let mut flags = SQLITE_UTF8;
+
+        if self.deterministic {
+            flags |= SQLITE_DETERMINISTIC;
+        }
+
+        // released by [destroy] when the function is replaced or the connection is closed;
+        // SQLite also calls [destroy] if the registration fails
+        let app = Box::into_raw(Box::new(self.kind.clone())) as *mut c_void;
+
+        // <https://www.sqlite.org/c3ref/create_function.html>
+        let status = unsafe {
+            match &self.kind {
+                FunctionKind::Scalar(_) => sqlite3_create_function_v2(
+                    handle,
+                    name.as_ptr(),
+                    self.num_args,
+                    flags,
+                    app,
+                    Some(call_scalar),
+                    None,
+                    None,
+                    Some(destroy),
+                ),
+
+                FunctionKind::Aggregate(aggregate) if aggregate.inverse.is_some() => {
+                    sqlite3_create_window_function(
+                        handle,
+                        name.as_ptr(),
+                        self.num_args,
+                        flags,
+                        app,
+                        Some(call_step),
+                        Some(call_final),
+                        Some(call_value),
+                        Some(call_inverse),
+                        Some(destroy),
+                    )
+                }
+
+                FunctionKind::Aggregate(_) => sqlite3_create_function_v2(
+                    handle,
+                    name.as_ptr(),
+                    self.num_args,
+                    flags,
+                    app,
+                    None,
+                    Some(call_step),
+                    Some(call_final),
+                    Some(destroy),
+                ),
+            }
+        };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::new(handle))));
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for SqliteFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteFunction")
+            .field("name", &self.name)
+            .field("num_args", &self.num_args)
+            .field("deterministic", &self.deterministic)
+            .finish()
+    }
+}
+
+/// The arguments of an application-defined function, as a tuple.
+///
+/// This is implemented for tuples of up to 8 types which implement [`Decode`].
+pub trait FunctionArgs: Sized {
+    #[doc(hidden)]
+    const ARITY: c_int;
+
+    #[doc(hidden)]
+    fn decode(values: &[SqliteValueRef<'_>]) -> Result<Self, BoxDynError>;
+}
+
+/// A closure which can be called as a scalar function.
+///
+/// This is implemented for any `Fn(A1, A2, ...) -> R` where the arguments implement
+/// [`FunctionArgs`].
+pub trait ScalarFunction<A, R>: Send + Sync + 'static {
+    #[doc(hidden)]
+    fn call(&self, args: A) -> R;
+}
+
+/// A closure which can be called for each row of an aggregate function.
+///
+/// This is implemented for any `Fn(&mut S, A1, A2, ...)` where the arguments implement
+/// [`FunctionArgs`].
+pub trait StepFunction<S, A>: Send + Sync + 'static {
+    #[doc(hidden)]
+    fn call(&self, state: &mut S, args: A);
+}
+
+macro_rules! impl_function_args {
+    ($arity:literal $(, $A:ident: $index:tt)*) => {
+        impl<$($A,)*> FunctionArgs for ($($A,)*)
+        where
+            $($A: for<'r> Decode<'r, Sqlite>,)*
+        {
+            const ARITY: c_int = $arity;
+
+            #[allow(unused_variables)]
+            fn decode(values: &[SqliteValueRef<'_>]) -> Result<Self, BoxDynError> {
+                Ok(($(<$A as Decode<'_, Sqlite>>::decode(values[$index].clone())?,)*))
+            }
+        }
+
+        impl<F, R, $($A,)*> ScalarFunction<($($A,)*), R> for F
+        where
+            F: Fn($($A),*) -> R + Send + Sync + 'static,
+        {
+            #[allow(non_snake_case)]
+            fn call(&self, ($($A,)*): ($($A,)*)) -> R {
+                self($($A),*)
+            }
+        }
+
+        impl<F, S, $($A,)*> StepFunction<S, ($($A,)*)> for F
+        where
+            F: Fn(&mut S, $($A),*) + Send + Sync + 'static,
+        {
+            #[allow(non_snake_case)]
+            fn call(&self, state: &mut S, ($($A,)*): ($($A,)*)) {
+                self(state, $($A),*)
+            }
+        }
+    };
+}
+
+impl_function_args!(0);
+impl_function_args!(1, A1: 0);
+impl_function_args!(2, A1: 0, A2: 1);
+impl_function_args!(3, A1: 0, A2: 1, A3: 2);
+impl_function_args!(4, A1: 0, A2: 1, A3: 2, A4: 3);
+impl_function_args!(5, A1: 0, A2: 1, A3: 2, A4: 3, A5: 4);
+impl_function_args!(6, A1: 0, A2: 1, A3: 2, A4: 3, A5: 4, A6: 5);
+impl_function_args!(7, A1: 0, A2: 1, A3: 2, A4: 3, A5: 4, A6: 5, A7: 6);
+impl_function_args!(8, A1: 0, A2: 1, A3: 2, A4: 3, A5: 4, A6: 5, A7: 6, A8: 7);
+
+fn erase_step<S, A, F>(step: F) -> Box<StepFn>
+where
+    S: 'static,
+    A: FunctionArgs,
+    F: StepFunction<S, A>,
+{
+    Box::new(move |state, values| {
+        let state = state.downcast_mut().expect("aggregate state type");
+        step.call(state, A::decode(values)?);
+
+        Ok(())
+    })
+}
+
+fn downcast<S: 'static>(state: State) -> S {
+    *state.downcast().expect("aggregate state type")
+}
+
+fn encode_result<'q, R: Encode<'q, Sqlite>>(value: R) -> SqliteArgumentValue<'q> {
+    let mut buf = Vec::with_capacity(1);
+
+    if let IsNull::Yes = value.encode(&mut buf) {
+        return SqliteArgumentValue::Null;
+    }
+
+    buf.pop().unwrap_or(SqliteArgumentValue::Null)
+}
+
+// the values passed to a function call, which are valid for the duration of the call
+unsafe fn values<'a>(argc: c_int, argv: *mut *mut sqlite3_value) -> Vec<SqliteValueRef<'a>> {
+    if argc == 0 {
+        return Vec::new();
+    }
+
+    slice::from_raw_parts(argv, argc as usize)
+        .iter()
+        .map(|value| SqliteValueRef::argument(value))
+        .collect()
+}
+
+unsafe fn kind<'a>(ctx: *mut sqlite3_context) -> &'a FunctionKind {
+    // SAFE: set to a [FunctionKind] in [SqliteFunction::register]
+    &*(sqlite3_user_data(ctx) as *const FunctionKind)
+}
+
+unsafe fn aggregate<'a>(ctx: *mut sqlite3_context) -> &'a Aggregate {
+    match kind(ctx) {
+        FunctionKind::Aggregate(aggregate) => aggregate,
+        FunctionKind::Scalar(_) => unreachable!(),
+    }
+}
+
+// <https://www.sqlite.org/c3ref/aggregate_context.html>
+// the aggregate context holds a pointer to the boxed state, which is created on first use
+unsafe fn state<'a>(ctx: *mut sqlite3_context, aggregate: &Aggregate) -> Option<&'a mut State> {
+    let ptr =
+        sqlite3_aggregate_context(ctx, mem::size_of::<*mut State>() as c_int) as *mut *mut State;
+
+    if ptr.is_null() {
+        sqlite3_result_error_nomem(ctx);
+        return None;
+    }
+
+    if (*ptr).is_null() {
+        *ptr = Box::into_raw(Box::new((aggregate.init)()));
+    }
+
+    Some(&mut **ptr)
+}
+
+unsafe extern "C" fn destroy(app: *mut c_void) {
+    drop(Box::from_raw(app as *mut FunctionKind));
+}
+
+unsafe extern "C" fn call_scalar(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    // a panic must not unwind into SQLite
+    let result = catch_unwind(AssertUnwindSafe(|| match kind(ctx) {
+        FunctionKind::Scalar(function) => function(&values(argc, argv)),
+        FunctionKind::Aggregate(_) => unreachable!(),
+    }));
+
+    set_result(ctx, result);
+}
+
+unsafe extern "C" fn call_step(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let aggregate = aggregate(ctx);
+
+    if let Some(state) = state(ctx, aggregate) {
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            (aggregate.step)(state, &values(argc, argv))
+        }));
+
+        set_error(ctx, result);
+    }
+}
+
+unsafe extern "C" fn call_inverse(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let aggregate = aggregate(ctx);
+
+    if let (Some(inverse), Some(state)) = (&aggregate.inverse, state(ctx, aggregate)) {
+        let result = catch_unwind(AssertUnwindSafe(|| inverse(state, &values(argc, argv))));
+
+        set_error(ctx, result);
+    }
+}
+
+unsafe extern "C" fn call_value(ctx: *mut sqlite3_context) {
+    let aggregate = aggregate(ctx);
+
+    if let (Some(value), Some(state)) = (&aggregate.value, state(ctx, aggregate)) {
+        let result = catch_unwind(AssertUnwindSafe(|| Ok(value(state))));
+
+        set_result(ctx, result);
+    }
+}
+
+unsafe extern "C" fn call_final(ctx: *mut sqlite3_context) {
+    let aggregate = aggregate(ctx);
+
+    // a size of 0 does not allocate, leaving a NULL pointer when there were no rows
+    let ptr = sqlite3_aggregate_context(ctx, 0) as *mut *mut State;
+
+    let state = if ptr.is_null() || (*ptr).is_null() {
+        (aggregate.init)()
+    } else {
+        *Box::from_raw(mem::replace(&mut *ptr, std::ptr::null_mut()))
+    };
+
+    let result = catch_unwind(AssertUnwindSafe(|| Ok((aggregate.finalize)(state))));
+
+    set_result(ctx, result);
+}
+
+unsafe fn set_result(
+    ctx: *mut sqlite3_context,
+    result: thread::Result<Result<SqliteArgumentValue<'_>, BoxDynError>>,
+) {
+    // <https://www.sqlite.org/c3ref/result_blob.html>
+    match result {
+        Ok(Ok(SqliteArgumentValue::Null)) => sqlite3_result_null(ctx),
+
+        Ok(Ok(SqliteArgumentValue::Text(v))) => sqlite3_result_text64(
+            ctx,
+            v.as_ptr() as *const c_char,
+            v.len() as u64,
+            SQLITE_TRANSIENT(),
+            SQLITE_UTF8 as u8,
+        ),
+
+        Ok(Ok(SqliteArgumentValue::Blob(v))) => sqlite3_result_blob64(
+            ctx,
+            v.as_ptr() as *const c_void,
+            v.len() as u64,
+            SQLITE_TRANSIENT(),
+        ),
+
+        Ok(Ok(SqliteArgumentValue::Double(v))) => sqlite3_result_double(ctx, v),
+        Ok(Ok(SqliteArgumentValue::Int(v))) => sqlite3_result_int(ctx, v),
+        Ok(Ok(SqliteArgumentValue::Int64(v))) => sqlite3_result_int64(ctx, v),
+
+        Ok(Err(error)) => result_error(ctx, &error.to_string()),
+        Err(_) => result_error(ctx, "application-defined function panicked"),
+    }
+}
+
+unsafe fn set_error(ctx: *mut sqlite3_context, result: thread::Result<Result<(), BoxDynError>>) {
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => result_error(ctx, &error.to_string()),
+        Err(_) => result_error(ctx, "application-defined function panicked"),
+    }
+}
+
+unsafe fn result_error(ctx: *mut sqlite3_context, message: &str) {
+    // SQLite makes its own copy of the message
+    sqlite3_result_error(
+        ctx,
+        message.as_ptr() as *const c_char,
+        message.len() as c_int,
+    );
+}
@@ -0,0 +1,250 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process;
+use std::ptr::null_mut;
+use std::str::from_utf8_unchecked;
+
+use libsqlite3_sys::{
+    sqlite3_commit_hook, sqlite3_int64, sqlite3_rollback_hook, sqlite3_update_hook, SQLITE_DELETE,
+    SQLITE_INSERT, SQLITE_UPDATE,
+};
+
+use crate::sqlite::connection::ConnectionState;
+use crate::sqlite::SqliteConnection;
+
+type UpdateHook = Box<dyn FnMut(SqliteUpdate<'_>) + Send + 'static>;
+type CommitHook = Box<dyn FnMut() -> bool + Send + 'static>;
+type RollbackHook = Box<dyn FnMut() + Send + 'static>;
+
+// the callbacks registered on a connection; each is boxed twice so SQLite can be handed a
+// thin pointer to it
+#[derive(Default)]
+pub(crate) struct Hooks {
+    update: Option<Box<UpdateHook>>,
+    commit: Option<Box<CommitHook>>,
+    rollback: Option<Box<RollbackHook>>,
+}
+
+/// The kind of change reported to an update hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A row change reported to the hook set with [`SqliteConnection::set_update_hook`].
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteUpdate<'a> {
+    operation: SqliteOperation,
+    database: &'a str,
+    table: &'a str,
+    rowid: i64,
+}
+
+impl<'a> SqliteUpdate<'a> {
+    /// Whether the row was inserted, updated or deleted.
+    pub fn operation(&self) -> SqliteOperation {
+        self.operation
+    }
+
+    /// The name of the database that holds the table, such as `main` or `temp`.
+    pub fn database(&self) -> &'a str {
+        self.database
+    }
+
+    /// The name of the table the row belongs to.
+    pub fn table(&self) -> &'a str {
+        self.table
+    }
+
+    /// The rowid of the row that was changed.
+    pub fn rowid(&self) -> i64 {
+        self.rowid
+    }
+}
+
+impl SqliteConnection {
+    /// Sets a callback invoked whenever a row is inserted, updated or deleted in a
+    /// [rowid table](https://www.sqlite.org/rowidtable.html) through this connection,
+    /// replacing any previous one.
+    ///
+    /// Changes made by `WITHOUT ROWID` tables, by truncating a table with an unqualified
+    /// `DELETE` or as a result of `ON CONFLICT REPLACE` are not reported. The callback must
+    /// not panic; a panic cannot be propagated through SQLite and aborts the process.
+    ///
+    /// See <https://www.sqlite.org/c3ref/update_hook.html>.
+    pub fn set_update_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(SqliteUpdate<'_>) + Send + 'static,
+    {
+        let mut hook: Box<UpdateHook> = Box::new(Box::new(hook));
+
+        self.change_hooks(move |conn| {
+            unsafe {
+                sqlite3_update_hook(
+                    conn.handle.as_ptr(),
+                    Some(call_update_hook),
+                    &mut *hook as *mut UpdateHook as *mut c_void,
+                );
+            }
+
+            // the previous hook is dropped only once SQLite no longer refers to it
+            conn.hooks.update = Some(hook);
+        });
+    }
+
+    /// Removes the callback set with [`set_update_hook`](Self::set_update_hook).
+    pub fn clear_update_hook(&mut self) {
+        self.change_hooks(|conn| {
+            unsafe {
+                sqlite3_update_hook(conn.handle.as_ptr(), None, null_mut());
+            }
+
+            conn.hooks.update = None;
+        });
+    }
+
+    /// Sets a callback invoked whenever a transaction is about to be committed, replacing any
+    /// previous one.
+    ///
+    /// Returning `false` turns the commit into a rollback and the statement that was
+    /// committing fails. The callback must not panic; a panic cannot be propagated through
+    /// SQLite and aborts the process.
+    ///
+    /// See <https://www.sqlite.org/c3ref/commit_hook.html>.
+    pub fn set_commit_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        let mut hook: Box<CommitHook> = Box::new(Box::new(hook));
+
+        self.change_hooks(move |conn| {
+            unsafe {
+                sqlite3_commit_hook(
+                    conn.handle.as_ptr(),
+                    Some(call_commit_hook),
+                    &mut *hook as *mut CommitHook as *mut c_void,
+                );
+            }
+
+            conn.hooks.commit = Some(hook);
+        });
+    }
+
+    /// Removes the callback set with [`set_commit_hook`](Self::set_commit_hook).
+    pub fn clear_commit_hook(&mut self) {
+        self.change_hooks(|conn| {
+            unsafe {
+                sqlite3_commit_hook(conn.handle.as_ptr(), None, null_mut());
+            }
+
+            conn.hooks.commit = None;
+        });
+    }
+
+    /// Sets a callback invoked whenever a transaction is rolled back, replacing any
+    /// previous one.
+    ///
+    /// This includes rollbacks caused by an error or by a commit hook returning `false`,
+    /// but not the rollback of an open transaction when the connection is closed. The
+    /// callback must not panic; a panic cannot be propagated through SQLite and aborts
+    /// the process.
+    ///
+    /// See <https://www.sqlite.org/c3ref/commit_hook.html>.
+    pub fn set_rollback_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let mut hook: Box<RollbackHook> = Box::new(Box::new(hook));
+
+        self.change_hooks(move |conn| {
+            unsafe {
+                sqlite3_rollback_hook(
+                    conn.handle.as_ptr(),
+                    Some(call_rollback_hook),
+                    &mut *hook as *mut RollbackHook as *mut c_void,
+                );
+            }
+
+            conn.hooks.rollback = Some(hook);
+        });
+    }
+
+    /// Removes the callback set with [`set_rollback_hook`](Self::set_rollback_hook).
+    pub fn clear_rollback_hook(&mut self) {
+        self.change_hooks(|conn| {
+            unsafe {
+                sqlite3_rollback_hook(conn.handle.as_ptr(), None, null_mut());
+            }
+
+            conn.hooks.rollback = None;
+        });
+    }
+
+    // hooks are set on the worker thread; as commands run in order, there is no need to wait
+    // for it before the next one
+    fn change_hooks(&mut self, f: impl FnOnce(&mut ConnectionState) + Send + 'static) {
+        // NOTE: ignore a crashed worker; the connection cannot run anything that would call
+        // the hook anymore
+        let _ = self.worker.run_detached(f);
+    }
+}
+
+// a hook cannot report an error and a panic must not unwind into SQLite
+fn guard<R>(f: impl FnOnce() -> R) -> R {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(_) => process::abort(),
+    }
+}
+
+unsafe extern "C" fn call_update_hook(
+    hook: *mut c_void,
+    operation: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    rowid: sqlite3_int64,
+) {
+    let hook = &mut *(hook as *mut UpdateHook);
+
+    let operation = match operation {
+        SQLITE_INSERT => SqliteOperation::Insert,
+        SQLITE_UPDATE => SqliteOperation::Update,
+        SQLITE_DELETE => SqliteOperation::Delete,
+
+        // SQLite documents no other operation
+        _ => return,
+    };
+
+    // SAFE: database and table names are always UTF-8
+    let database = from_utf8_unchecked(CStr::from_ptr(database).to_bytes());
+    let table = from_utf8_unchecked(CStr::from_ptr(table).to_bytes());
+
+    guard(|| {
+        hook(SqliteUpdate {
+            operation,
+            database,
+            table,
+            rowid,
+        })
+    });
+}
+
+unsafe extern "C" fn call_commit_hook(hook: *mut c_void) -> c_int {
+    let hook = &mut *(hook as *mut CommitHook);
+
+    // a non-zero return converts the commit into a rollback
+    if guard(hook) {
+        0
+    } else {
+        1
+    }
+}
+
+unsafe extern "C" fn call_rollback_hook(hook: *mut c_void) {
+    let hook = &mut *(hook as *mut RollbackHook);
+
+    guard(hook);
+}
@@ -6,9 +6,15 @@
 #![allow(unsafe_code)]
 
 mod arguments;
+mod backup;
+mod blob;
+mod collation;
 mod connection;
 mod database;
 mod error;
+mod extension;
+mod function;
+mod hooks;
 mod options;
 mod row;
 mod statement;
@@ -18,10 +24,14 @@ pub mod types;
 mod value;
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
+pub use backup::{SqliteBackup, SqliteBackupProgress, SqliteBackupTarget};
+pub use blob::SqliteBlob;
 pub use connection::SqliteConnection;
 pub use database::Sqlite;
 pub use error::SqliteError;
-pub use options::SqliteConnectOptions;
+pub use function::{FunctionArgs, ScalarFunction, SqliteFunction, StepFunction};
+pub use hooks::{SqliteOperation, SqliteUpdate};
+pub use options::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
 pub use row::SqliteRow;
 pub use transaction::SqliteTransactionManager;
 pub use type_info::SqliteTypeInfo;
@@ -1,15 +1,175 @@
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::error::BoxDynError;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use url::form_urlencoded;
 
-// TODO: Look at go-sqlite for option ideas
-// TODO: journal_mode
+use crate::error::{BoxDynError, Error};
+use crate::sqlite::collation::Collation;
+use crate::sqlite::connection::ConnectionHandle;
+use crate::sqlite::extension::Extension;
+use crate::sqlite::SqliteFunction;
+
+// characters that cannot appear as-is in the path of a URI filename
+// <https://www.sqlite.org/uri.html#uri_filenames_in_sqlite>
+const URI_PATH: &AsciiSet = &CONTROLS.add(b' ').add(b'#').add(b'%').add(b'?');
+
+/// The journal mode of a SQLite database.
+///
+/// It is used by the [`journal_mode`](SqliteConnectOptions::journal_mode) method.
+///
+/// See <https://www.sqlite.org/pragma.html#pragma_journal_mode>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteJournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    Wal,
+    Off,
+}
+
+impl SqliteJournalMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SqliteJournalMode::Delete => "DELETE",
+            SqliteJournalMode::Truncate => "TRUNCATE",
+            SqliteJournalMode::Persist => "PERSIST",
+            SqliteJournalMode::Memory => "MEMORY",
+            SqliteJournalMode::Wal => "WAL",
+            SqliteJournalMode::Off => "OFF",
+        }
+    }
+}
+
+impl FromStr for SqliteJournalMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "delete" => SqliteJournalMode::Delete,
+            "truncate" => SqliteJournalMode::Truncate,
+            "persist" => SqliteJournalMode::Persist,
+            "memory" => SqliteJournalMode::Memory,
+            "wal" => SqliteJournalMode::Wal,
+            "off" => SqliteJournalMode::Off,
+
+            _ => {
+                return Err(err_protocol!("unknown journal mode value: {:?}", s));
+            }
+        })
+    }
+}
+
+/// How hard SQLite works to make sure data has reached the disk before continuing.
+///
+/// It is used by the [`synchronous`](SqliteConnectOptions::synchronous) method.
+///
+/// See <https://www.sqlite.org/pragma.html#pragma_synchronous>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteSynchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl SqliteSynchronous {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SqliteSynchronous::Off => "OFF",
+            SqliteSynchronous::Normal => "NORMAL",
+            SqliteSynchronous::Full => "FULL",
+            SqliteSynchronous::Extra => "EXTRA",
+        }
+    }
+}
+
+impl FromStr for SqliteSynchronous {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "off" => SqliteSynchronous::Off,
+            "normal" => SqliteSynchronous::Normal,
+            "full" => SqliteSynchronous::Full,
+            "extra" => SqliteSynchronous::Extra,
+
+            _ => {
+                return Err(err_protocol!("unknown synchronous value: {:?}", s));
+            }
+        })
+    }
+}
 
 /// Options and flags which can be used to configure a SQLite connection.
+///
+/// A value of `SqliteConnectOptions` can be parsed from a connection URI.
+///
+/// The generic format of the connection URI:
+///
+/// ```text
+/// sqlite://[filename][?param1=value1&...]
+/// ```
+///
+/// The filename `:memory:` opens a private, temporary in-memory database and an empty
+/// filename opens a private, temporary on-disk database.
+///
+/// | Parameter      | Values                                      |
+/// |----------------|---------------------------------------------|
+/// | `mode`         | `ro`, `rw`, `rwc` or `memory`               |
+/// | `journal_mode` | `delete`, `truncate`, `persist`, `memory`, `wal` or `off` |
+/// | `synchronous`  | `off`, `normal`, `full` or `extra`          |
+/// | `foreign_keys` | `true` or `false`                           |
+/// | `busy_timeout` | a number of milliseconds                    |
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use sqlx_core::error::Error;
+/// # use sqlx_core::connection::Connect;
+/// # use sqlx_core::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode};
+/// # use std::time::Duration;
+/// #
+/// # fn main() -> Result<(), Error> {
+/// # #[cfg(feature = "runtime-async-std")]
+/// # sqlx_rt::async_std::task::block_on(async move {
+/// // URI connection string
+/// let conn = SqliteConnection::connect("sqlite://data.db?mode=ro").await?;
+///
+/// // Manually-constructed options
+/// let conn = SqliteConnection::connect_with(&SqliteConnectOptions::new()
+///     .filename("data.db")
+///     .journal_mode(SqliteJournalMode::Wal)
+///     .foreign_keys(true)
+///     .busy_timeout(Duration::from_secs(30))
+/// ).await?;
+/// # Ok(())
+/// # })
+/// # }
+/// ```
+#[derive(Debug, Clone)]
 pub struct SqliteConnectOptions {
     pub(crate) filename: PathBuf,
     pub(crate) in_memory: bool,
+    pub(crate) shared_cache: bool,
+    pub(crate) read_only: bool,
+    pub(crate) create_if_missing: bool,
+    pub(crate) journal_mode: Option<SqliteJournalMode>,
+    pub(crate) synchronous: Option<SqliteSynchronous>,
+    pub(crate) foreign_keys: Option<bool>,
+    pub(crate) busy_timeout: Duration,
+    pub(crate) extensions: Vec<Extension>,
+    pub(crate) functions: Vec<SqliteFunction>,
+    pub(crate) collations: Vec<Collation>,
+    pub(crate) stub_unknown_collations: bool,
+
+    // a connection that keeps a shared in-memory database alive for as long as these
+    // options, and so any pool created from them, are around
+    pub(crate) keep_alive: Option<Arc<Mutex<Option<ConnectionHandle>>>>,
 }
 
 impl Default for SqliteConnectOptions {
@@ -19,34 +179,363 @@ impl Default for SqliteConnectOptions {
 }
 
 impl SqliteConnectOptions {
+    /// Creates a new, default set of options ready for configuration.
+    ///
+    /// By default, this opens a private, temporary in-memory database.
     pub fn new() -> Self {
         Self {
             filename: PathBuf::from(":memory:"),
             in_memory: false,
+            shared_cache: false,
+            read_only: false,
+            create_if_missing: true,
+            journal_mode: None,
+            synchronous: None,
+            foreign_keys: None,
+            busy_timeout: Duration::from_secs(5),
+            extensions: Vec::new(),
+            functions: Vec::new(),
+            collations: Vec::new(),
+            stub_unknown_collations: false,
+            keep_alive: None,
         }
     }
+
+    /// Sets the name of the database file.
+    ///
+    /// A name starting with `file:` is interpreted as a [URI](https://www.sqlite.org/uri.html).
+    pub fn filename(mut self, filename: impl AsRef<Path>) -> Self {
+        self.filename = filename.as_ref().to_owned();
+        self
+    }
+
+    /// Sets whether the database is held purely in memory.
+    ///
+    /// This is the same as `mode=memory` in the connection URI.
+    pub fn in_memory(mut self, in_memory: bool) -> Self {
+        self.in_memory = in_memory;
+        self
+    }
+
+    /// Sets whether the connection uses [shared-cache mode](https://www.sqlite.org/sharedcache.html).
+    ///
+    /// This is the same as `cache=shared` in the connection URI.
+    pub fn shared_cache(mut self, shared_cache: bool) -> Self {
+        self.shared_cache = shared_cache;
+        self
+    }
+
+    /// Opens a named in-memory database that is shared by every connection opened with
+    /// these options, such as the connections of a [`SqlitePool`](super::SqlitePool).
+    ///
+    /// The database is kept alive for as long as these options, or any pool created from
+    /// them, are. This is the same as a `file:<name>?mode=memory&cache=shared` URI.
+    ///
+    /// Connections to the same shared cache lock tables rather than the whole database; a
+    /// statement that cannot get a lock fails with `SQLITE_LOCKED` without waiting for the
+    /// [`busy_timeout`](Self::busy_timeout).
+    pub fn shared_memory(mut self, name: &str) -> Self {
+        // SQLite only shares an in-memory database by name when it is opened with a URI
+        self.filename = format!(
+            "file:{}?mode=memory&cache=shared",
+            utf8_percent_encode(name, URI_PATH)
+        )
+        .into();
+
+        self.in_memory = true;
+        self.shared_cache = true;
+        self.keep_alive = Some(Default::default());
+        self
+    }
+
+    /// Sets whether to open the database for reading only.
+    ///
+    /// This is the same as `mode=ro` in the connection URI.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets whether to create the database file if it does not already exist.
+    ///
+    /// This is enabled by default. It has no effect on a read-only connection.
+    pub fn create_if_missing(mut self, create: bool) -> Self {
+        self.create_if_missing = create;
+        self
+    }
+
+    /// Sets the [journal mode](https://www.sqlite.org/pragma.html#pragma_journal_mode)
+    /// of the database.
+    ///
+    /// When not set, the journal mode the database already has is left alone.
+    pub fn journal_mode(mut self, mode: SqliteJournalMode) -> Self {
+        self.journal_mode = Some(mode);
+        self
+    }
+
+    /// Sets the [synchronous](https://www.sqlite.org/pragma.html#pragma_synchronous)
+    /// setting of the connection.
+    ///
+    /// When not set, SQLite defaults to `FULL`.
+    pub fn synchronous(mut self, synchronous: SqliteSynchronous) -> Self {
+        self.synchronous = Some(synchronous);
+        self
+    }
+
+    /// Sets whether [foreign key constraints](https://www.sqlite.org/foreignkeys.html)
+    /// are enforced.
+    ///
+    /// When not set, this is decided by how SQLite was compiled, which is disabled
+    /// unless changed.
+    pub fn foreign_keys(mut self, on: bool) -> Self {
+        self.foreign_keys = Some(on);
+        self
+    }
+
+    /// Sets how long to wait for a locked database to become available before
+    /// returning a busy error.
+    ///
+    /// The default busy timeout is 5 seconds.
+    pub fn busy_timeout(mut self, timeout: Duration) -> Self {
+        self.busy_timeout = timeout;
+        self
+    }
+
+    /// Registers an application-defined SQL function on each new connection.
+    ///
+    /// See [`SqliteFunction`] for the kinds of function which can be registered.
+    pub fn function(mut self, function: SqliteFunction) -> Self {
+        self.functions.push(function);
+        self
+    }
+
+    /// Loads a [run-time loadable extension](https://www.sqlite.org/loadext.html) on each new
+    /// connection.
+    ///
+    /// The `path` is passed to SQLite as-is, which tries again with the shared library suffix
+    /// of the platform appended if it cannot be loaded. Unless an `entry_point` is given,
+    /// SQLite derives it from the file name.
+    ///
+    /// Extension loading is only enabled while the listed extensions are loaded; SQL can never
+    /// call the `load_extension()` function.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// let options = SqliteConnectOptions::new()
+    ///     .filename("geo.db")
+    ///     .extension("mod_spatialite", None);
+    /// ```
+    pub fn extension(mut self, path: impl AsRef<Path>, entry_point: Option<&str>) -> Self {
+        self.extensions
+            .push(Extension::new(path.as_ref(), entry_point));
+
+        self
+    }
+
+    /// Registers a [collating sequence](https://www.sqlite.org/datatype3.html#collation)
+    /// on each new connection.
+    ///
+    /// The collation can then be used by name, as in `ORDER BY name COLLATE natural`.
+    /// The comparison must be consistent; a panic inside it aborts the process as it cannot
+    /// be reported to SQLite. Text that is not valid UTF-8 is compared with the invalid
+    /// sequences replaced by `U+FFFD`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// let options = SqliteConnectOptions::new()
+    ///     .collation("nocase_rev", |a, b| b.to_lowercase().cmp(&a.to_lowercase()));
+    /// ```
+    pub fn collation<F>(mut self, name: &str, compare: F) -> Self
+    where
+        F: Fn(&str, &str) -> Ordering + Send + Sync + 'static,
+    {
+        self.collations.push(Collation::new(name, compare));
+        self
+    }
+
+    // used by the query macros, which cannot know the collations registered at runtime
+    #[doc(hidden)]
+    pub fn __stub_unknown_collations(mut self) -> Self {
+        self.stub_unknown_collations = true;
+        self
+    }
+
+    // the PRAGMA statements run on each new connection
+    pub(crate) fn pragmas(&self) -> String {
+        let mut pragmas = String::new();
+
+        if let Some(mode) = self.journal_mode {
+            pragmas += &format!("PRAGMA journal_mode = {}; ", mode.as_str());
+        }
+
+        if let Some(synchronous) = self.synchronous {
+            pragmas += &format!("PRAGMA synchronous = {}; ", synchronous.as_str());
+        }
+
+        if let Some(on) = self.foreign_keys {
+            pragmas += &format!("PRAGMA foreign_keys = {}; ", if on { "ON" } else { "OFF" });
+        }
+
+        pragmas
+    }
 }
 
 impl FromStr for SqliteConnectOptions {
     type Err = BoxDynError;
 
     fn from_str(mut s: &str) -> Result<Self, Self::Err> {
-        let mut options = Self {
-            filename: PathBuf::new(),
-            in_memory: false,
-        };
+        let mut options = Self::new();
 
         // remove scheme
         s = s
             .trim_start_matches("sqlite://")
             .trim_start_matches("sqlite:");
 
-        if s == ":memory:" {
+        let (filename, query) = match s.find('?') {
+            Some(index) => (&s[..index], &s[index + 1..]),
+            None => (s, ""),
+        };
+
+        if filename.starts_with("file:") {
+            // a URI filename is passed to SQLite as a whole, which understands the same
+            // `mode` and `cache` parameters as we do below
+            // <https://www.sqlite.org/uri.html>
+            options.filename = s.into();
+        } else if filename == ":memory:" {
             options.in_memory = true;
+            options.filename = PathBuf::new();
         } else {
-            options.filename = s.parse()?;
+            options.filename = filename.parse()?;
+        }
+
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            match &*key {
+                // <https://www.sqlite.org/uri.html#urimode>
+                "mode" => match &*value {
+                    "ro" => {
+                        options.read_only = true;
+                    }
+
+                    "rw" => {
+                        options.read_only = false;
+                        options.create_if_missing = false;
+                    }
+
+                    "rwc" => {
+                        options.read_only = false;
+                        options.create_if_missing = true;
+                    }
+
+                    "memory" => {
+                        options.in_memory = true;
+                    }
+
+                    _ => {
+                        return Err(format!("unknown value {:?} for `mode`", value).into());
+                    }
+                },
+
+                // <https://www.sqlite.org/uri.html#uricache>
+                "cache" => match &*value {
+                    "shared" => {
+                        options.shared_cache = true;
+                    }
+
+                    "private" => {
+                        options.shared_cache = false;
+                    }
+
+                    _ => {
+                        return Err(format!("unknown value {:?} for `cache`", value).into());
+                    }
+                },
+
+                "journal_mode" => {
+                    options = options.journal_mode(value.parse()?);
+                }
+
+                "synchronous" => {
+                    options = options.synchronous(value.parse()?);
+                }
+
+                "foreign_keys" => {
+                    options = options.foreign_keys(parse_bool(&key, &value)?);
+                }
+
+                "busy_timeout" => {
+                    let millis = value.parse().map_err(|_| {
+                        format!(
+                            "expected a number of milliseconds for `busy_timeout`, got {:?}",
+                            value
+                        )
+                    })?;
+
+                    options = options.busy_timeout(Duration::from_millis(millis));
+                }
+
+                _ => {}
+            }
+        }
+
+        if options.in_memory && options.shared_cache {
+            options.keep_alive = Some(Default::default());
         }
 
         Ok(options)
     }
 }
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, BoxDynError> {
+    match &*value.to_ascii_lowercase() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+
+        _ => Err(format!("expected a boolean for `{}`, got {:?}", key, value).into()),
+    }
+}
+
+#[test]
+fn test_parse_options() -> Result<(), BoxDynError> {
+    let options: SqliteConnectOptions = "sqlite::memory:".parse()?;
+    assert!(options.in_memory);
+
+    let options: SqliteConnectOptions = "sqlite://data.db?mode=ro".parse()?;
+    assert_eq!(options.filename, Path::new("data.db"));
+    assert!(options.read_only);
+
+    let options: SqliteConnectOptions = "sqlite:data.db?mode=rw&journal_mode=wal\
+        &synchronous=normal&foreign_keys=on&busy_timeout=250"
+        .parse()?;
+
+    assert!(!options.read_only);
+    assert!(!options.create_if_missing);
+    assert_eq!(options.journal_mode, Some(SqliteJournalMode::Wal));
+    assert_eq!(options.synchronous, Some(SqliteSynchronous::Normal));
+    assert_eq!(options.foreign_keys, Some(true));
+    assert_eq!(options.busy_timeout, Duration::from_millis(250));
+
+    let options: SqliteConnectOptions = "sqlite:file:shared?mode=memory&cache=shared".parse()?;
+    assert_eq!(
+        options.filename,
+        Path::new("file:shared?mode=memory&cache=shared")
+    );
+    assert!(options.in_memory);
+    assert!(options.shared_cache);
+    assert!(options.keep_alive.is_some());
+
+    let options = SqliteConnectOptions::new().shared_memory("100% shared?");
+    assert_eq!(
+        options.filename,
+        Path::new("file:100%25%20shared%3F?mode=memory&cache=shared")
+    );
+
+    assert!("sqlite:data.db?journal_mode=fast"
+        .parse::<SqliteConnectOptions>()
+        .is_err());
+
+    Ok(())
+}
@@ -2,24 +2,22 @@ use std::ffi::c_void;
 use std::ffi::CStr;
 use std::os::raw::{c_char, c_int};
 use std::ptr::NonNull;
-use std::str::{from_utf8, from_utf8_unchecked};
+use std::str::from_utf8_unchecked;
 
 use libsqlite3_sys::{
     sqlite3, sqlite3_bind_blob64, sqlite3_bind_double, sqlite3_bind_int, sqlite3_bind_int64,
     sqlite3_bind_null, sqlite3_bind_parameter_count, sqlite3_bind_parameter_name,
-    sqlite3_bind_text64, sqlite3_changes, sqlite3_column_blob, sqlite3_column_bytes,
-    sqlite3_column_count, sqlite3_column_database_name, sqlite3_column_decltype,
-    sqlite3_column_double, sqlite3_column_int, sqlite3_column_int64, sqlite3_column_name,
-    sqlite3_column_origin_name, sqlite3_column_table_name, sqlite3_column_type,
-    sqlite3_column_value, sqlite3_db_handle, sqlite3_stmt, sqlite3_table_column_metadata,
-    SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+    sqlite3_bind_text64, sqlite3_changes, sqlite3_column_count, sqlite3_column_database_name,
+    sqlite3_column_decltype, sqlite3_column_name, sqlite3_column_origin_name,
+    sqlite3_column_table_name, sqlite3_column_value, sqlite3_db_handle, sqlite3_reset, sqlite3_sql,
+    sqlite3_step, sqlite3_stmt, sqlite3_stmt_isexplain, sqlite3_table_column_metadata, SQLITE_OK,
+    SQLITE_TRANSIENT, SQLITE_UTF8,
 };
 
-use crate::error::{BoxDynError, Error};
+use crate::error::Error;
 use crate::sqlite::type_info::DataType;
 use crate::sqlite::{SqliteError, SqliteTypeInfo, SqliteValue};
 use std::ptr;
-use std::slice::from_raw_parts;
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct StatementHandle(pub(super) NonNull<sqlite3_stmt>);
@@ -43,6 +41,19 @@ impl StatementHandle {
         SqliteError::new(unsafe { self.db_handle() })
     }
 
+    #[inline]
+    pub(crate) fn step(&self) -> c_int {
+        // https://www.sqlite.org/c3ref/step.html
+        unsafe { sqlite3_step(self.0.as_ptr()) }
+    }
+
+    #[inline]
+    pub(crate) fn reset(&self) {
+        // NOTE: ignore the result here, it is the error of the last step if there was one
+        // https://www.sqlite.org/c3ref/reset.html
+        unsafe { sqlite3_reset(self.0.as_ptr()) };
+    }
+
     #[inline]
     pub(crate) fn column_count(&self) -> usize {
         // https://sqlite.org/c3ref/column_count.html
@@ -57,6 +68,23 @@ impl StatementHandle {
         unsafe { sqlite3_changes(self.db_handle()) as u64 }
     }
 
+    #[inline]
+    pub(crate) fn sql(&self) -> &str {
+        // https://sqlite.org/c3ref/expanded_sql.html
+        unsafe {
+            let sql = sqlite3_sql(self.0.as_ptr());
+            debug_assert!(!sql.is_null());
+
+            from_utf8_unchecked(CStr::from_ptr(sql).to_bytes())
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_explain(&self) -> bool {
+        // https://sqlite.org/c3ref/stmt_isexplain.html
+        unsafe { sqlite3_stmt_isexplain(self.0.as_ptr()) != 0 }
+    }
+
     #[inline]
     pub(crate) fn column_name(&self, index: usize) -> &str {
         // https://sqlite.org/c3ref/column_name.html
@@ -208,47 +236,8 @@ impl StatementHandle {
     // result values from the query
     // https://www.sqlite.org/c3ref/column_blob.html
 
-    #[inline]
-    pub(crate) fn column_type(&self, index: usize) -> c_int {
-        unsafe { sqlite3_column_type(self.0.as_ptr(), index as c_int) }
-    }
-
-    #[inline]
-    pub(crate) fn column_int(&self, index: usize) -> i32 {
-        unsafe { sqlite3_column_int(self.0.as_ptr(), index as c_int) as i32 }
-    }
-
-    #[inline]
-    pub(crate) fn column_int64(&self, index: usize) -> i64 {
-        unsafe { sqlite3_column_int64(self.0.as_ptr(), index as c_int) as i64 }
-    }
-
-    #[inline]
-    pub(crate) fn column_double(&self, index: usize) -> f64 {
-        unsafe { sqlite3_column_double(self.0.as_ptr(), index as c_int) }
-    }
-
     #[inline]
     pub(crate) fn column_value(&self, index: usize) -> SqliteValue {
         unsafe { SqliteValue::new(sqlite3_column_value(self.0.as_ptr(), index as c_int)) }
     }
-
-    pub(crate) fn column_blob(&self, index: usize) -> &[u8] {
-        let index = index as c_int;
-        let len = unsafe { sqlite3_column_bytes(self.0.as_ptr(), index) } as usize;
-
-        if len == 0 {
-            // empty blobs are NULL so just return an empty slice
-            return &[];
-        }
-
-        let ptr = unsafe { sqlite3_column_blob(self.0.as_ptr(), index) } as *const u8;
-        debug_assert!(!ptr.is_null());
-
-        unsafe { from_raw_parts(ptr, len) }
-    }
-
-    pub(crate) fn column_text(&self, index: usize) -> Result<&str, BoxDynError> {
-        Ok(from_utf8(self.column_blob(index))?)
-    }
 }
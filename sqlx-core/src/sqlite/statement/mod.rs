@@ -1,7 +1,6 @@
 use std::i32;
 use std::os::raw::c_char;
 use std::ptr::{null, null_mut, NonNull};
-use std::sync::{atomic::AtomicPtr, Weak};
 
 use bytes::{Buf, Bytes};
 use libsqlite3_sys::{
@@ -12,13 +11,11 @@ use smallvec::SmallVec;
 
 use crate::error::Error;
 use crate::sqlite::connection::ConnectionHandle;
-use crate::sqlite::{SqliteError, SqliteRow, SqliteValue};
+use crate::sqlite::SqliteError;
 
 mod handle;
-mod worker;
 
 pub(crate) use handle::StatementHandle;
-pub(crate) use worker::StatementWorker;
 
 // NOTE: Keep query in statement and slowly chop it up
 
@@ -34,12 +31,6 @@ pub(crate) struct SqliteStatement {
     // a SQL query string in SQLite is broken up into N statements
     // we use a [`SmallVec`] to optimize for the most likely case of a single statement
     pub(crate) handles: SmallVec<[StatementHandle; 1]>,
-
-    // weak reference to the previous row from this connection
-    // we use the notice of a successful upgrade of this reference as an indicator that the
-    // row is still around, in which we then inflate the row such that we can let SQLite
-    // clobber the memory allocation for the row
-    pub(crate) last_row_values: SmallVec<[Option<Weak<AtomicPtr<SqliteValue>>>; 1]>,
 }
 
 fn prepare(
@@ -122,7 +113,6 @@ impl SqliteStatement {
             tail: query,
             handles,
             index: 0,
-            last_row_values: SmallVec::from([None; 1]),
         })
     }
 
@@ -131,9 +121,7 @@ impl SqliteStatement {
         self.handles[0].db_handle()
     }
 
-    pub(crate) fn execute(
-        &mut self,
-    ) -> Result<Option<(&StatementHandle, &mut Option<Weak<AtomicPtr<SqliteValue>>>)>, Error> {
+    pub(crate) fn execute(&mut self) -> Result<Option<&StatementHandle>, Error> {
         while self.handles.len() == self.index {
             if self.tail.is_empty() {
                 return Ok(None);
@@ -143,25 +131,19 @@ impl SqliteStatement {
                 unsafe { prepare(self.connection(), &mut self.tail, self.persistent)? }
             {
                 self.handles.push(handle);
-                self.last_row_values.push(None);
             }
         }
 
         let index = self.index;
         self.index += 1;
 
-        Ok(Some((
-            &self.handles[index],
-            &mut self.last_row_values[index],
-        )))
+        Ok(Some(&self.handles[index]))
     }
 
     pub(crate) fn reset(&mut self) {
         self.index = 0;
 
-        for (i, handle) in self.handles.iter().enumerate() {
-            SqliteRow::inflate_if_needed(&handle, self.last_row_values[i].take());
-
+        for handle in &self.handles {
             unsafe {
                 // Reset A Prepared Statement Object
                 // https://www.sqlite.org/c3ref/reset.html
@@ -175,9 +157,7 @@ impl SqliteStatement {
 
 impl Drop for SqliteStatement {
     fn drop(&mut self) {
-        for (i, handle) in self.handles.drain(..).enumerate() {
-            SqliteRow::inflate_if_needed(&handle, self.last_row_values[i].take());
-
+        for handle in self.handles.drain(..) {
             unsafe {
                 // https://sqlite.org/c3ref/finalize.html
                 let _ = sqlite3_finalize(handle.0.as_ptr());
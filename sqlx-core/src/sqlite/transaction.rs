@@ -47,7 +47,9 @@ impl TransactionManager for SqliteTransactionManager {
         z_query.push_str(&query);
         z_query.push('\0');
 
-        unsafe {
+        // NOTE: the worker runs this before any later command of the connection; if it has
+        //       crashed, there is no transaction left to roll back
+        let _ = conn.worker.run_detached(move |conn| unsafe {
             // NOTE: this is a direct execution as a ROLLBACK is unlikely to block for any amount of time
             let status = sqlite3_exec(
                 conn.handle.as_ptr(),
@@ -63,6 +65,6 @@ impl TransactionManager for SqliteTransactionManager {
                     SqliteError::new(conn.handle.as_ptr())
                 );
             }
-        }
+        });
     }
 }
@@ -7,21 +7,21 @@ use libsqlite3_sys::{SQLITE_BLOB, SQLITE_FLOAT, SQLITE_INTEGER, SQLITE_NULL, SQL
 use crate::error::BoxDynError;
 use crate::type_info::TypeInfo;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum DataType {
     Int,
     Float,
     Text,
     Blob,
-
-    // TODO: Support NUMERIC
-    #[allow(dead_code)]
     Numeric,
 
     // non-standard extensions
     Bool,
     Int64,
+    Date,
+    Time,
+    Datetime,
 }
 
 /// Type information for a SQLite type.
@@ -37,6 +37,18 @@ impl Display for SqliteTypeInfo {
 
 impl TypeInfo for SqliteTypeInfo {}
 
+impl SqliteTypeInfo {
+    #[doc(hidden)]
+    pub fn __type_feature_gate(&self) -> Option<&'static str> {
+        match self.0 {
+            DataType::Numeric => Some("bigdecimal"),
+            DataType::Date | DataType::Time | DataType::Datetime => Some("time"),
+
+            _ => None,
+        }
+    }
+}
+
 impl Display for DataType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.write_str(match self {
@@ -49,6 +61,9 @@ impl Display for DataType {
             // non-standard extensions
             DataType::Bool => "BOOLEAN",
             DataType::Int64 => "BIGINT",
+            DataType::Date => "DATE",
+            DataType::Time => "TIME",
+            DataType::Datetime => "DATETIME",
         })
     }
 }
@@ -65,6 +80,25 @@ impl DataType {
             _ => None,
         }
     }
+
+    // numerics, dates and times are only ever a *declared* type as SQLite stores them
+    // as TEXT, REAL or INTEGER
+    pub(crate) fn is_declared_only(&self) -> bool {
+        matches!(
+            self,
+            DataType::Numeric | DataType::Date | DataType::Time | DataType::Datetime
+        )
+    }
+
+    // whether a value of this type can be decoded as the given declared-only type, which is
+    // either declared as it or stored in one of the storage classes SQLite keeps it as
+    pub(crate) fn can_hold(&self, declared: DataType) -> bool {
+        *self == declared
+            || matches!(
+                self,
+                DataType::Text | DataType::Float | DataType::Int | DataType::Int64
+            )
+    }
 }
 
 // note: this implementation is particularly important as this is how the macros determine
@@ -79,6 +113,12 @@ impl FromStr for DataType {
             "int8" => DataType::Int64,
             "boolean" | "bool" => DataType::Bool,
 
+            "date" => DataType::Date,
+            "time" => DataType::Time,
+            "datetime" | "timestamp" => DataType::Datetime,
+
+            _ if s.starts_with("numeric") || s.starts_with("decimal") => DataType::Numeric,
+
             _ if s.contains("int") && s.contains("big") && s.find("int") > s.find("big") => {
                 DataType::Int64
             }
@@ -123,5 +163,27 @@ fn test_data_type_from_str() -> Result<(), BoxDynError> {
     assert_eq!(DataType::Bool, "BOOLEAN".parse()?);
     assert_eq!(DataType::Bool, "BOOL".parse()?);
 
+    assert_eq!(DataType::Date, "DATE".parse()?);
+    assert_eq!(DataType::Time, "TIME".parse()?);
+    assert_eq!(DataType::Datetime, "DATETIME".parse()?);
+    assert_eq!(DataType::Datetime, "TIMESTAMP".parse()?);
+
+    assert_eq!(DataType::Numeric, "NUMERIC".parse()?);
+    assert_eq!(DataType::Numeric, "NUMERIC(10, 5)".parse()?);
+    assert_eq!(DataType::Numeric, "DECIMAL(10,5)".parse()?);
+
     Ok(())
 }
+
+#[test]
+fn test_data_type_can_hold() {
+    assert!(DataType::Date.can_hold(DataType::Date));
+    assert!(DataType::Text.can_hold(DataType::Date));
+    assert!(DataType::Int64.can_hold(DataType::Datetime));
+
+    // one declared type is never taken for another
+    assert!(!DataType::Numeric.can_hold(DataType::Date));
+    assert!(!DataType::Date.can_hold(DataType::Numeric));
+    assert!(!DataType::Datetime.can_hold(DataType::Time));
+    assert!(!DataType::Blob.can_hold(DataType::Date));
+}
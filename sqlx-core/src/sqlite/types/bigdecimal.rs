@@ -0,0 +1,44 @@
+use bigdecimal::BigDecimal;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use crate::types::Type;
+use crate::value::ValueRef;
+
+impl Type<Sqlite> for BigDecimal {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Numeric)
+    }
+}
+
+impl Encode<'_, Sqlite> for BigDecimal {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        // encoded as TEXT so no precision is lost; a column with NUMERIC affinity will
+        // still store it as an INTEGER or REAL when that conversion is lossless
+        Encode::<Sqlite>::encode(self.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for BigDecimal {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Numeric)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        // SQLite has no storage class for NUMERIC, a value with NUMERIC affinity is stored
+        // as INTEGER, REAL or TEXT depending on what can represent it
+        // <https://www.sqlite.org/datatype3.html#type_affinity>
+        match value.type_info().as_deref().map(|ty| &ty.0) {
+            Some(DataType::Int) | Some(DataType::Int64) => Ok(BigDecimal::from(value.int64())),
+
+            // the shortest representation that round-trips, which avoids carrying the binary
+            // noise of the float into the decimal
+            Some(DataType::Float) => Ok(value.double().to_string().parse()?),
+
+            _ => Ok(value.text()?.parse()?),
+        }
+    }
+}
@@ -20,8 +20,8 @@ impl<'q> Encode<'q, Sqlite> for bool {
 }
 
 impl<'r> Decode<'r, Sqlite> for bool {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<bool, BoxDynError> {
@@ -22,8 +22,8 @@ impl<'q> Encode<'q, Sqlite> for &'q [u8] {
 }
 
 impl<'r> Decode<'r, Sqlite> for &'r [u8] {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
@@ -52,8 +52,8 @@ impl<'q> Encode<'q, Sqlite> for Vec<u8> {
 }
 
 impl<'r> Decode<'r, Sqlite> for Vec<u8> {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
@@ -0,0 +1,224 @@
+use std::fmt::Display;
+
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc,
+};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use crate::types::Type;
+use crate::value::ValueRef;
+
+impl<Tz: TimeZone> Type<Sqlite> for DateTime<Tz> {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Datetime)
+    }
+}
+
+impl Type<Sqlite> for NaiveDateTime {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Datetime)
+    }
+}
+
+impl Type<Sqlite> for NaiveDate {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Date)
+    }
+}
+
+impl Type<Sqlite> for NaiveTime {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Time)
+    }
+}
+
+impl<Tz: TimeZone> Encode<'_, Sqlite> for DateTime<Tz>
+where
+    Tz::Offset: Display,
+{
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        Encode::<Sqlite>::encode(self.format("%F %T%.f%:z").to_string(), buf)
+    }
+}
+
+impl Encode<'_, Sqlite> for NaiveDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        Encode::<Sqlite>::encode(self.format("%F %T%.f").to_string(), buf)
+    }
+}
+
+impl Encode<'_, Sqlite> for NaiveDate {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        Encode::<Sqlite>::encode(self.format("%F").to_string(), buf)
+    }
+}
+
+impl Encode<'_, Sqlite> for NaiveTime {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        Encode::<Sqlite>::encode(self.format("%T%.f").to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for DateTime<Utc> {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Datetime)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Utc.from_utc_datetime(&decode_datetime(value)?.naive_utc()))
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for DateTime<Local> {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Datetime)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Local.from_utc_datetime(&decode_datetime(value)?.naive_utc()))
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for DateTime<FixedOffset> {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Datetime)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_datetime(value)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for NaiveDateTime {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Datetime)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(decode_datetime(value)?.naive_local())
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for NaiveDate {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Date)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        if is_text(&value) {
+            if let Ok(date) = NaiveDate::parse_from_str(value.text()?, "%F") {
+                return Ok(date);
+            }
+        }
+
+        Ok(decode_datetime(value)?.naive_local().date())
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for NaiveTime {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Time)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        if is_text(&value) {
+            let text = value.text()?;
+
+            // SQLite accepts `HH:MM` and `HH:MM:SS[.SSS]` for a bare time of day
+            for format in &["%T%.f", "%R"] {
+                if let Ok(time) = NaiveTime::parse_from_str(text, format) {
+                    return Ok(time);
+                }
+            }
+        }
+
+        Ok(decode_datetime(value)?.naive_local().time())
+    }
+}
+
+fn is_text(value: &SqliteValueRef<'_>) -> bool {
+    matches!(
+        value.type_info().as_deref(),
+        Some(SqliteTypeInfo(DataType::Text))
+    )
+}
+
+// SQLite has no storage class for dates and times; the built-in date and time functions
+// understand ISO-8601 TEXT, REAL as a julian day number and INTEGER as a unix timestamp
+// <https://www.sqlite.org/lang_datefunc.html>
+fn decode_datetime(value: SqliteValueRef<'_>) -> Result<DateTime<FixedOffset>, BoxDynError> {
+    let dt = match value.type_info().as_deref().map(|ty| &ty.0) {
+        Some(DataType::Text) => decode_datetime_from_text(value.text()?),
+        Some(DataType::Int) | Some(DataType::Int64) => decode_datetime_from_int(value.int64()),
+        Some(DataType::Float) => decode_datetime_from_float(value.double()),
+
+        _ => None,
+    };
+
+    dt.ok_or_else(|| "value is not a valid SQLite date and time".into())
+}
+
+fn decode_datetime_from_text(value: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt);
+    }
+
+    // the formats recognized by SQLite, with and without a time zone suffix
+    let formats = &[
+        "%F %T%.f",
+        "%F %T%.f%:z",
+        "%F %T%.fZ",
+        "%F %R",
+        "%F %R%:z",
+        "%F %RZ",
+        "%FT%T%.f",
+        "%FT%T%.f%:z",
+        "%FT%T%.fZ",
+        "%FT%R",
+        "%FT%R%:z",
+        "%FT%RZ",
+    ];
+
+    for format in formats {
+        if let Ok(dt) = DateTime::parse_from_str(value, format) {
+            return Some(dt);
+        }
+
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Utc.fix().from_utc_datetime(&dt));
+        }
+    }
+
+    // a date on its own is midnight of that day
+    NaiveDate::parse_from_str(value, "%F")
+        .ok()
+        .map(|date| Utc.fix().from_utc_datetime(&date.and_hms(0, 0, 0)))
+}
+
+fn decode_datetime_from_int(value: i64) -> Option<DateTime<FixedOffset>> {
+    NaiveDateTime::from_timestamp_opt(value, 0).map(|dt| Utc.fix().from_utc_datetime(&dt))
+}
+
+fn decode_datetime_from_float(value: f64) -> Option<DateTime<FixedOffset>> {
+    // the julian day number of the unix epoch, 1970-01-01 00:00:00 UTC
+    const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+
+    // SQLite itself only keeps julian days to the millisecond
+    let millis = ((value - UNIX_EPOCH_JULIAN_DAY) * 86_400_000.0).round();
+
+    if !millis.is_finite() || millis.abs() > i64::MAX as f64 {
+        return None;
+    }
+
+    let millis = millis as i64;
+
+    NaiveDateTime::from_timestamp_opt(
+        millis.div_euclid(1000),
+        (millis.rem_euclid(1000) * 1_000_000) as u32,
+    )
+    .map(|dt| Utc.fix().from_utc_datetime(&dt))
+}
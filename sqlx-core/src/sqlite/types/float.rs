@@ -20,8 +20,8 @@ impl<'q> Encode<'q, Sqlite> for f32 {
 }
 
 impl<'r> Decode<'r, Sqlite> for f32 {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<f32, BoxDynError> {
@@ -44,8 +44,8 @@ impl<'q> Encode<'q, Sqlite> for f64 {
 }
 
 impl<'r> Decode<'r, Sqlite> for f64 {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<f64, BoxDynError> {
@@ -1,3 +1,5 @@
+use std::convert::TryInto;
+
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
@@ -5,6 +7,54 @@ use crate::sqlite::type_info::DataType;
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
 use crate::types::Type;
 
+impl Type<Sqlite> for i8 {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Int)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for i8 {
+    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        args.push(SqliteArgumentValue::Int(*self as i32));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for i8 {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.int64().try_into().map_err(Into::into)
+    }
+}
+
+impl Type<Sqlite> for i16 {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Int)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for i16 {
+    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        args.push(SqliteArgumentValue::Int(*self as i32));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for i16 {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.int64().try_into().map_err(Into::into)
+    }
+}
+
 impl Type<Sqlite> for i32 {
     fn type_info() -> SqliteTypeInfo {
         SqliteTypeInfo(DataType::Int)
@@ -20,8 +70,8 @@ impl<'q> Encode<'q, Sqlite> for i32 {
 }
 
 impl<'r> Decode<'r, Sqlite> for i32 {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
@@ -44,8 +94,8 @@ impl<'q> Encode<'q, Sqlite> for i64 {
 }
 
 impl<'r> Decode<'r, Sqlite> for i64 {
-    fn accepts(_ty: &SqliteTypeInfo) -> bool {
-        true
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        !ty.0.is_declared_only()
     }
 
     fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
@@ -5,14 +5,75 @@
 //! | Rust type                             | SQLite type(s)                                       |
 //! |---------------------------------------|------------------------------------------------------|
 //! | `bool`                                | BOOLEAN                                              |
+//! | `i8`                                  | INTEGER                                              |
 //! | `i16`                                 | INTEGER                                              |
 //! | `i32`                                 | INTEGER                                              |
 //! | `i64`                                 | BIGINT, INT8                                         |
+//! | `u8`                                  | INTEGER                                              |
+//! | `u16`                                 | INTEGER                                              |
+//! | `u32`                                 | BIGINT, INT8                                         |
+//! | `u64`                                 | BIGINT, INT8                                         |
 //! | `f32`                                 | REAL                                                 |
 //! | `f64`                                 | REAL                                                 |
 //! | `&str`, `String`                      | TEXT                                                 |
 //! | `&[u8]`, `Vec<u8>`                    | BLOB                                                 |
 //!
+//! SQLite stores every integer as a signed 64-bit INTEGER. Decoding into a smaller or
+//! unsigned Rust type returns an error if the value is out of range for it. As not every `u64`
+//! fits in an INTEGER, `u64` can only be decoded, not encoded.
+//!
+//! ### [`bigdecimal`](https://crates.io/crates/bigdecimal)
+//!
+//! Requires the `bigdecimal` Cargo feature flag.
+//!
+//! | Rust type                             | SQLite type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bigdecimal::BigDecimal`              | NUMERIC, DECIMAL                                     |
+//!
+//! A `BigDecimal` is encoded as TEXT and can be decoded from INTEGER, REAL or TEXT, which
+//! are the storage classes SQLite may pick for a value in a NUMERIC column.
+//!
+//! ### [`chrono`](https://crates.io/crates/chrono)
+//!
+//! Requires the `chrono` Cargo feature flag.
+//!
+//! | Rust type                             | SQLite type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `chrono::DateTime<Utc>`               | DATETIME                                             |
+//! | `chrono::DateTime<Local>`             | DATETIME                                             |
+//! | `chrono::NaiveDateTime`               | DATETIME                                             |
+//! | `chrono::NaiveDate`                   | DATE                                                 |
+//! | `chrono::NaiveTime`                   | TIME                                                 |
+//!
+//! ### [`time`](https://crates.io/crates/time)
+//!
+//! Requires the `time` Cargo feature flag.
+//!
+//! | Rust type                             | SQLite type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `time::PrimitiveDateTime`             | DATETIME                                             |
+//! | `time::OffsetDateTime`                | DATETIME                                             |
+//! | `time::Date`                          | DATE                                                 |
+//! | `time::Time`                          | TIME                                                 |
+//!
+//! SQLite has no dedicated storage class for dates and times. Values are encoded as ISO-8601
+//! TEXT and can be decoded from TEXT, from REAL as a julian day number and from INTEGER as a
+//! unix timestamp, which are the forms understood by the SQLite
+//! [date and time functions](https://www.sqlite.org/lang_datefunc.html).
+//!
+//! ### [`json`](https://crates.io/crates/serde_json)
+//!
+//! Requires the `json` Cargo feature flag.
+//!
+//! | Rust type                             | SQLite type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `Json<T>`                             | TEXT                                                 |
+//! | `serde_json::JsonValue`               | TEXT                                                 |
+//! | `&serde_json::value::RawValue`        | TEXT                                                 |
+//!
+//! JSON is stored as TEXT, which is what the functions of the
+//! [JSON1 extension](https://www.sqlite.org/json1.html) take and return.
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
@@ -22,9 +83,27 @@
 // NOTE: all types are compatible with all other types in SQLite
 //       so we explicitly opt-out of runtime type assertions by returning [true] for
 //       all implementations of [Decode::accepts]
+//
+//       the exception are the numeric, date and time types, which can only come from the
+//       declared type of a column; these are refused by the primitive types so that the query
+//       macros do not quietly map a DATETIME column to `bool` when the `chrono` or `time`
+//       feature is disabled
 
 mod bool;
 mod bytes;
 mod float;
 mod int;
 mod str;
+mod uint;
+
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal;
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "json")]
+mod json;
@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use crate::types::Type;
+use crate::value::ValueRef;
+
+// the range of unix timestamps that SQLite supports, 0000-01-01 up to 9999-12-31
+const MIN_TIMESTAMP: i64 = -62_167_219_200;
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+impl Type<Sqlite> for OffsetDateTime {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Datetime)
+    }
+}
+
+impl Type<Sqlite> for PrimitiveDateTime {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Datetime)
+    }
+}
+
+impl Type<Sqlite> for Date {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Date)
+    }
+}
+
+impl Type<Sqlite> for Time {
+    fn type_info() -> SqliteTypeInfo {
+        SqliteTypeInfo(DataType::Time)
+    }
+}
+
+impl Encode<'_, Sqlite> for OffsetDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        let minutes = self.offset().as_minutes();
+
+        let text = format!(
+            "{} {}{}{:02}:{:02}",
+            self.date().format("%Y-%m-%d"),
+            encode_time(self.time()),
+            if minutes < 0 { '-' } else { '+' },
+            minutes.abs() / 60,
+            minutes.abs() % 60,
+        );
+
+        Encode::<Sqlite>::encode(text, buf)
+    }
+}
+
+impl Encode<'_, Sqlite> for PrimitiveDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        let text = format!(
+            "{} {}",
+            self.date().format("%Y-%m-%d"),
+            encode_time(self.time())
+        );
+
+        Encode::<Sqlite>::encode(text, buf)
+    }
+}
+
+impl Encode<'_, Sqlite> for Date {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        Encode::<Sqlite>::encode(self.format("%Y-%m-%d"), buf)
+    }
+}
+
+impl Encode<'_, Sqlite> for Time {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'_>>) -> IsNull {
+        Encode::<Sqlite>::encode(encode_time(*self), buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for OffsetDateTime {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Datetime)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_datetime(value)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for PrimitiveDateTime {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Datetime)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let dt = decode_datetime(value)?;
+
+        Ok(dt.date().with_time(dt.time()))
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Date {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Date)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        if is_text(&value) {
+            if let Ok(date) = Date::parse(value.text()?, "%Y-%m-%d") {
+                return Ok(date);
+            }
+        }
+
+        Ok(decode_datetime(value)?.date())
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Time {
+    fn accepts(ty: &SqliteTypeInfo) -> bool {
+        ty.0.can_hold(DataType::Time)
+    }
+
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        if is_text(&value) {
+            if let Some(time) = decode_time_from_text(value.text()?) {
+                return Ok(time);
+            }
+        }
+
+        Ok(decode_datetime(value)?.time())
+    }
+}
+
+// fractional seconds are written with 3, 6 or 9 digits as needed, the same as `%.f` in chrono
+fn encode_time(time: Time) -> String {
+    let hms = time.format("%H:%M:%S");
+    let fraction = format!("{:09}", time.nanosecond());
+
+    match fraction.trim_end_matches('0').len() {
+        0 => hms,
+        1..=3 => format!("{}.{}", hms, &fraction[..3]),
+        4..=6 => format!("{}.{}", hms, &fraction[..6]),
+        _ => format!("{}.{}", hms, fraction),
+    }
+}
+
+fn is_text(value: &SqliteValueRef<'_>) -> bool {
+    matches!(
+        value.type_info().as_deref(),
+        Some(SqliteTypeInfo(DataType::Text))
+    )
+}
+
+// SQLite has no storage class for dates and times; the built-in date and time functions
+// understand ISO-8601 TEXT, REAL as a julian day number and INTEGER as a unix timestamp
+// <https://www.sqlite.org/lang_datefunc.html>
+fn decode_datetime(value: SqliteValueRef<'_>) -> Result<OffsetDateTime, BoxDynError> {
+    let dt = match value.type_info().as_deref().map(|ty| &ty.0) {
+        Some(DataType::Text) => decode_datetime_from_text(value.text()?),
+        Some(DataType::Int) | Some(DataType::Int64) => decode_datetime_from_int(value.int64()),
+        Some(DataType::Float) => decode_datetime_from_float(value.double()),
+
+        _ => None,
+    };
+
+    dt.ok_or_else(|| "value is not a valid SQLite date and time".into())
+}
+
+// `YYYY-MM-DD`, optionally followed by a space or `T` and a time of day, optionally
+// followed by a `[+-]HH:MM` or `Z` time zone suffix
+fn decode_datetime_from_text(value: &str) -> Option<OffsetDateTime> {
+    let date = Date::parse(value.get(..10)?, "%Y-%m-%d").ok()?;
+    let rest = value.get(10..)?;
+
+    if rest.is_empty() {
+        return Some(date.midnight().assume_utc());
+    }
+
+    if !rest.starts_with(' ') && !rest.starts_with('T') {
+        return None;
+    }
+
+    let rest = &rest[1..];
+
+    let (time, offset) = match rest.find(&['Z', '+', '-'][..]) {
+        Some(index) => (&rest[..index], decode_offset_from_text(&rest[index..])?),
+        None => (rest, UtcOffset::UTC),
+    };
+
+    Some(
+        date.with_time(decode_time_from_text(time)?)
+            .assume_offset(offset),
+    )
+}
+
+// `HH:MM`, `HH:MM:SS` or `HH:MM:SS.SSS` with any number of fractional digits
+fn decode_time_from_text(value: &str) -> Option<Time> {
+    let (hms, fraction) = match value.find('.') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+
+    let hms = if hms.len() == 5 {
+        Cow::Owned(format!("{}:00", hms))
+    } else {
+        Cow::Borrowed(hms)
+    };
+
+    let time = Time::parse(&*hms, "%H:%M:%S").ok()?;
+
+    // only nanosecond precision is kept, any further digits are truncated
+    let nanos = format!("{:0<9}", fraction.get(..9).unwrap_or(fraction))
+        .parse()
+        .ok()?;
+
+    Time::try_from_hms_nano(time.hour(), time.minute(), time.second(), nanos).ok()
+}
+
+fn decode_offset_from_text(value: &str) -> Option<UtcOffset> {
+    if value == "Z" {
+        return Some(UtcOffset::UTC);
+    }
+
+    UtcOffset::parse(value.replace(':', ""), "%z").ok()
+}
+
+fn decode_datetime_from_int(value: i64) -> Option<OffsetDateTime> {
+    if !(MIN_TIMESTAMP..=MAX_TIMESTAMP).contains(&value) {
+        return None;
+    }
+
+    Some(OffsetDateTime::from_unix_timestamp(value))
+}
+
+fn decode_datetime_from_float(value: f64) -> Option<OffsetDateTime> {
+    // the julian day number of the unix epoch, 1970-01-01 00:00:00 UTC
+    const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+
+    // SQLite itself only keeps julian days to the millisecond
+    let millis = ((value - UNIX_EPOCH_JULIAN_DAY) * 86_400_000.0).round();
+
+    if !(MIN_TIMESTAMP as f64 * 1000.0..=MAX_TIMESTAMP as f64 * 1000.0).contains(&millis) {
+        return None;
+    }
+
+    Some(OffsetDateTime::unix_epoch() + Duration::milliseconds(millis as i64))
+}
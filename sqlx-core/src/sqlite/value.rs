@@ -6,24 +6,25 @@ use std::sync::Arc;
 
 use libsqlite3_sys::{
     sqlite3_value, sqlite3_value_blob, sqlite3_value_bytes, sqlite3_value_double,
-    sqlite3_value_dup, sqlite3_value_int, sqlite3_value_int64, sqlite3_value_type, SQLITE_NULL,
+    sqlite3_value_dup, sqlite3_value_free, sqlite3_value_int, sqlite3_value_int64,
+    sqlite3_value_type, SQLITE_NULL,
 };
 
 use crate::error::BoxDynError;
-use crate::sqlite::statement::StatementHandle;
 use crate::sqlite::type_info::DataType;
 use crate::sqlite::{Sqlite, SqliteTypeInfo};
 use crate::value::{Value, ValueRef};
 
+#[derive(Clone)]
 enum SqliteValueData<'r> {
-    Statement {
-        statement: &'r StatementHandle,
-        index: usize,
-    },
-
     Value(&'r SqliteValue),
+
+    // an argument of an application-defined function, which SQLite keeps valid for the whole
+    // call so it is read in place rather than copied
+    Argument(&'r *mut sqlite3_value),
 }
 
+#[derive(Clone)]
 pub struct SqliteValueRef<'r>(SqliteValueData<'r>);
 
 impl<'r> SqliteValueRef<'r> {
@@ -31,43 +32,47 @@ impl<'r> SqliteValueRef<'r> {
         Self(SqliteValueData::Value(value))
     }
 
-    pub(crate) fn statement(statement: &'r StatementHandle, index: usize) -> Self {
-        Self(SqliteValueData::Statement { statement, index })
+    // SAFE: the value must be valid for as long as the reference to it is
+    pub(crate) unsafe fn argument(value: &'r *mut sqlite3_value) -> Self {
+        debug_assert!(!value.is_null());
+        Self(SqliteValueData::Argument(value))
     }
 
-    pub(super) fn int(&self) -> i32 {
+    fn as_ptr(&self) -> *mut sqlite3_value {
         match self.0 {
-            SqliteValueData::Statement { statement, index } => statement.column_int(index),
-            SqliteValueData::Value(v) => v.int(),
+            SqliteValueData::Value(v) => v.0.as_ptr(),
+            SqliteValueData::Argument(v) => *v,
         }
     }
 
+    pub(super) fn int(&self) -> i32 {
+        unsafe { sqlite3_value_int(self.as_ptr()) }
+    }
+
     pub(super) fn int64(&self) -> i64 {
-        match self.0 {
-            SqliteValueData::Statement { statement, index } => statement.column_int64(index),
-            SqliteValueData::Value(v) => v.int64(),
-        }
+        unsafe { sqlite3_value_int64(self.as_ptr()) }
     }
 
     pub(super) fn double(&self) -> f64 {
-        match self.0 {
-            SqliteValueData::Statement { statement, index } => statement.column_double(index),
-            SqliteValueData::Value(v) => v.double(),
-        }
+        unsafe { sqlite3_value_double(self.as_ptr()) }
     }
 
     pub(super) fn blob(&self) -> &'r [u8] {
-        match self.0 {
-            SqliteValueData::Statement { statement, index } => statement.column_blob(index),
-            SqliteValueData::Value(v) => v.blob(),
+        let len = unsafe { sqlite3_value_bytes(self.as_ptr()) } as usize;
+
+        if len == 0 {
+            // empty blobs are NULL so just return an empty slice
+            return &[];
         }
+
+        let ptr = unsafe { sqlite3_value_blob(self.as_ptr()) } as *const u8;
+        debug_assert!(!ptr.is_null());
+
+        unsafe { from_raw_parts(ptr, len) }
     }
 
     pub(super) fn text(&self) -> Result<&'r str, BoxDynError> {
-        match self.0 {
-            SqliteValueData::Statement { statement, index } => statement.column_text(index),
-            SqliteValueData::Value(v) => v.text(),
-        }
+        Ok(from_utf8(self.blob())?)
     }
 }
 
@@ -76,79 +81,51 @@ impl<'r> ValueRef<'r> for SqliteValueRef<'r> {
 
     fn to_owned(&self) -> SqliteValue {
         match self.0 {
-            SqliteValueData::Statement { statement, index } => statement.column_value(index),
             SqliteValueData::Value(v) => v.clone(),
+            SqliteValueData::Argument(v) => unsafe { SqliteValue::new(*v) },
         }
     }
 
     fn type_info(&self) -> Option<Cow<'_, SqliteTypeInfo>> {
-        match self.0 {
-            SqliteValueData::Statement { statement, index } => {
-                DataType::from_code(statement.column_type(index))
-                    .map(SqliteTypeInfo)
-                    .map(Cow::Owned)
-            }
-
-            SqliteValueData::Value(v) => v.type_info(),
-        }
+        DataType::from_code(unsafe { sqlite3_value_type(self.as_ptr()) })
+            .map(SqliteTypeInfo)
+            .map(Cow::Owned)
     }
 
     fn is_null(&self) -> bool {
-        match self.0 {
-            SqliteValueData::Statement { statement, index } => {
-                statement.column_type(index) == SQLITE_NULL
-            }
-
-            SqliteValueData::Value(v) => v.is_null(),
-        }
+        unsafe { sqlite3_value_type(self.as_ptr()) == SQLITE_NULL }
     }
 }
 
 #[derive(Clone)]
-pub struct SqliteValue(pub(crate) Arc<NonNull<sqlite3_value>>);
-
-// SAFE: only protected value objects are stored in SqliteValue
-unsafe impl Send for SqliteValue {}
-unsafe impl Sync for SqliteValue {}
-
-impl SqliteValue {
-    pub(crate) unsafe fn new(value: *mut sqlite3_value) -> Self {
-        debug_assert!(!value.is_null());
-        Self(Arc::new(NonNull::new_unchecked(sqlite3_value_dup(value))))
-    }
+pub struct SqliteValue(pub(crate) Arc<ValueHandle>);
 
-    fn r#type(&self) -> Option<DataType> {
-        DataType::from_code(unsafe { sqlite3_value_type(self.0.as_ptr()) })
-    }
+// a copy of a value, owned by us and freed once the last `SqliteValue` that shares it is dropped
+pub(crate) struct ValueHandle(NonNull<sqlite3_value>);
 
-    fn int(&self) -> i32 {
-        unsafe { sqlite3_value_int(self.0.as_ptr()) }
-    }
-
-    fn int64(&self) -> i64 {
-        unsafe { sqlite3_value_int64(self.0.as_ptr()) }
-    }
+// SAFE: only protected value objects are stored in ValueHandle
+unsafe impl Send for ValueHandle {}
+unsafe impl Sync for ValueHandle {}
 
-    fn double(&self) -> f64 {
-        unsafe { sqlite3_value_double(self.0.as_ptr()) }
+impl ValueHandle {
+    fn as_ptr(&self) -> *mut sqlite3_value {
+        self.0.as_ptr()
     }
+}
 
-    fn blob(&self) -> &[u8] {
-        let len = unsafe { sqlite3_value_bytes(self.0.as_ptr()) } as usize;
-
-        if len == 0 {
-            // empty blobs are NULL so just return an empty slice
-            return &[];
-        }
-
-        let ptr = unsafe { sqlite3_value_blob(self.0.as_ptr()) } as *const u8;
-        debug_assert!(!ptr.is_null());
-
-        unsafe { from_raw_parts(ptr, len) }
+impl Drop for ValueHandle {
+    fn drop(&mut self) {
+        // https://www.sqlite.org/c3ref/value_dup.html
+        unsafe { sqlite3_value_free(self.0.as_ptr()) }
     }
+}
 
-    fn text(&self) -> Result<&str, BoxDynError> {
-        Ok(from_utf8(self.blob())?)
+impl SqliteValue {
+    pub(crate) unsafe fn new(value: *mut sqlite3_value) -> Self {
+        debug_assert!(!value.is_null());
+        Self(Arc::new(ValueHandle(NonNull::new_unchecked(
+            sqlite3_value_dup(value),
+        ))))
     }
 }
 
@@ -160,10 +137,43 @@ impl Value for SqliteValue {
     }
 
     fn type_info(&self) -> Option<Cow<'_, SqliteTypeInfo>> {
-        self.r#type().map(SqliteTypeInfo).map(Cow::Owned)
+        DataType::from_code(unsafe { sqlite3_value_type(self.0.as_ptr()) })
+            .map(SqliteTypeInfo)
+            .map(Cow::Owned)
     }
 
     fn is_null(&self) -> bool {
         unsafe { sqlite3_value_type(self.0.as_ptr()) == SQLITE_NULL }
     }
 }
+
+#[cfg(all(test, feature = "runtime-async-std"))]
+#[test]
+fn test_values_are_freed() -> Result<(), crate::error::Error> {
+    use crate::connection::Connect;
+    use crate::executor::Executor;
+    use crate::sqlite::SqliteConnection;
+    use libsqlite3_sys::sqlite3_memory_used;
+
+    sqlx_rt::async_std::task::block_on(async {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+        // 1000 rows of 1 KiB each
+        let sql = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000) \
+            SELECT randomblob(1024) FROM n";
+
+        conn.fetch_all(sql).await?;
+
+        let used = unsafe { sqlite3_memory_used() };
+
+        for _ in 0..10 {
+            let rows = conn.fetch_all(sql).await?;
+            assert_eq!(rows.len(), 1000);
+        }
+
+        // were the values leaked, this would have grown by 10 MiB
+        assert!(unsafe { sqlite3_memory_used() } - used < 1024 * 1024);
+
+        Ok(())
+    })
+}
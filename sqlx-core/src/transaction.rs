@@ -75,12 +75,26 @@ where
     C: Sized + Connection<Database = DB>,
 {
     pub(crate) fn begin(conn: impl Into<MaybeOwned<'c, C>>) -> BoxFuture<'c, Result<Self, Error>> {
+        Self::begin_with(conn, DB::TransactionManager::begin)
+    }
+
+    // as `begin`, but with the transaction (or savepoint) started by the given function rather
+    // than by the transaction manager, for when the database has more options to begin with
+    pub(crate) fn begin_with<F>(
+        conn: impl Into<MaybeOwned<'c, C>>,
+        begin: F,
+    ) -> BoxFuture<'c, Result<Self, Error>>
+    where
+        F: for<'a> FnOnce(&'a mut DB::Connection, usize) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + 'c,
+    {
         let mut conn = conn.into();
 
         Box::pin(async move {
             let depth = conn.transaction_depth();
 
-            DB::TransactionManager::begin(conn.get_mut(), depth).await?;
+            begin(conn.get_mut(), depth).await?;
 
             Ok(Self {
                 depth: depth + 1,
@@ -2,6 +2,7 @@ use sqlx_core as sqlx;
 
 impl_database_ext! {
     sqlx::mssql::Mssql {
+        bool,
         i8,
         i16,
         i32,
@@ -9,9 +10,40 @@ impl_database_ext! {
         f32,
         f64,
         String,
+        Vec<u8>,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::NaiveTime,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::NaiveDate,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::NaiveDateTime,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::Time,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::Date,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::PrimitiveDateTime,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::OffsetDateTime,
+
+        #[cfg(feature = "uuid")]
+        sqlx::types::Uuid,
+
+        #[cfg(feature = "bigdecimal")]
+        sqlx::types::BigDecimal,
     },
     ParamChecking::Weak,
-    feature-types: _info => None,
+    feature-types: info => info.__type_feature_gate(),
     row = sqlx::mssql::MssqlRow,
     name = "MSSQL"
 }
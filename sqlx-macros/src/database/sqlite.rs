@@ -9,9 +9,36 @@ impl_database_ext! {
         f64,
         String,
         Vec<u8>,
+
+        #[cfg(feature = "bigdecimal")]
+        sqlx::types::BigDecimal,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::NaiveDate,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::NaiveTime,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::NaiveDateTime,
+
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::Date,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::Time,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::PrimitiveDateTime,
+
+        #[cfg(feature = "time")]
+        sqlx::types::time::OffsetDateTime,
     },
     ParamChecking::Weak,
-    feature-types: _info => None,
+    feature-types: info => info.__type_feature_gate(),
     row = sqlx::sqlite::SqliteRow,
     name = "SQLite"
 }
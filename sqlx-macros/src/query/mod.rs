@@ -105,8 +105,18 @@ fn expand_from_db(input: QueryMacroInput, db_url: &str) -> crate::Result<TokenSt
 
         #[cfg(feature = "sqlite")]
         "sqlite" => {
+            use sqlx_core::sqlite::{SqliteConnectOptions, SqliteConnection};
+
+            // collations are registered by the application at runtime; stub out any
+            // we do not know about so that the query can still be prepared
+            let options = db_url
+                .as_str()
+                .parse::<SqliteConnectOptions>()
+                .map_err(|e| e as crate::Error)?
+                .__stub_unknown_collations();
+
             let data = block_on(async {
-                let mut conn = sqlx_core::sqlite::SqliteConnection::connect(db_url.as_str()).await?;
+                let mut conn = SqliteConnection::connect_with(&options).await?;
                 QueryData::from_db(&mut conn, &input.src).await
             })?;
 
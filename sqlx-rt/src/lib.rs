@@ -27,8 +27,9 @@ pub use native_tls;
     any(feature = "runtime-tokio", feature = "runtime-actix"),
 ))]
 pub use tokio::{
-    self, fs, io::AsyncRead, io::AsyncReadExt, io::AsyncWrite, io::AsyncWriteExt, net::TcpStream,
-    task::spawn, task::yield_now, time::delay_for as sleep, time::timeout,
+    self, fs, io::AsyncRead, io::AsyncReadExt, io::AsyncSeek, io::AsyncSeekExt, io::AsyncWrite,
+    io::AsyncWriteExt, net::TcpStream, net::UdpSocket, task::spawn, task::yield_now,
+    time::delay_for as sleep, time::timeout,
 };
 
 #[cfg(all(
@@ -90,8 +91,9 @@ macro_rules! blocking {
 ))]
 pub use async_std::{
     self, fs, future::timeout, io::prelude::ReadExt as AsyncReadExt,
-    io::prelude::WriteExt as AsyncWriteExt, io::Read as AsyncRead, io::Write as AsyncWrite,
-    net::TcpStream, task::sleep, task::spawn, task::yield_now,
+    io::prelude::SeekExt as AsyncSeekExt, io::prelude::WriteExt as AsyncWriteExt,
+    io::Read as AsyncRead, io::Seek as AsyncSeek, io::Write as AsyncWrite, net::TcpStream,
+    net::UdpSocket, task::sleep, task::spawn, task::yield_now,
 };
 
 #[cfg(all(
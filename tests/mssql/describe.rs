@@ -35,3 +35,28 @@ async fn it_describes_simple() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_describes_params() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let d = conn
+        .describe("SELECT id, owner_id FROM tweet WHERE owner_id = @p2 AND id = @p1")
+        .await?;
+
+    let params: Vec<String> = d
+        .params
+        .iter()
+        .map(|param| param.as_ref().unwrap().to_string())
+        .collect();
+
+    assert_eq!(params, ["bigint", "bigint"]);
+
+    assert_eq!(d.columns[0].name, "id");
+    assert_eq!(d.columns[1].name, "owner_id");
+
+    assert_eq!(d.columns[0].not_null, Some(true));
+    assert_eq!(d.columns[1].not_null, Some(false));
+
+    Ok(())
+}
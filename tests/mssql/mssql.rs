@@ -1,5 +1,5 @@
 use futures::TryStreamExt;
-use sqlx::mssql::Mssql;
+use sqlx::mssql::{Mssql, MssqlConnection, MssqlIsolationLevel};
 use sqlx::{Connection, Executor, Row};
 use sqlx_core::mssql::MssqlRow;
 use sqlx_test::new;
@@ -107,6 +107,66 @@ async fn it_binds_empty_string_and_null() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_binds_and_selects_values_larger_than_a_packet() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let text = "x".repeat(20_000);
+    let bytes = vec![0xAB_u8; 50_000];
+
+    let (text2, bytes2): (String, Vec<u8>) = sqlx::query_as("SELECT @p1, @p2")
+        .bind(&text)
+        .bind(&bytes)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text, text2);
+    assert_eq!(bytes, bytes2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_selects_varchar_in_utf8_collation() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    // UTF-8 collations are new in SQL Server 2019
+    let (version,): (String,) =
+        sqlx::query_as("SELECT CAST(SERVERPROPERTY('ProductMajorVersion') AS NVARCHAR(10))")
+            .fetch_one(&mut conn)
+            .await?;
+
+    if version.parse::<u32>()? < 15 {
+        return Ok(());
+    }
+
+    let (text,): (String,) = sqlx::query_as(
+        "SELECT CAST(N'Grüße, 世界 👋' COLLATE Latin1_General_100_CI_AS_SC_UTF8 AS VARCHAR(50))",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(text, "Grüße, 世界 👋");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_strings_longer_than_nvarchar_4000_bytes() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    // 3000 characters take 6000 bytes, which is still short of `nvarchar(max)`
+    let text = "x".repeat(3000);
+    let text2: String = sqlx::query_scalar("SELECT @p1")
+        .bind(&text)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text, text2);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_work_with_transactions() -> anyhow::Result<()> {
     let mut conn = new::<Mssql>().await?;
@@ -221,3 +281,140 @@ async fn it_can_work_with_nested_transactions() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_can_begin_with_isolation_level() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    let mut tx = conn
+        .begin_with_isolation_level(MssqlIsolationLevel::Snapshot)
+        .await?;
+
+    // 5 = SNAPSHOT
+    let (level,): (i16,) = sqlx::query_as(
+        "SELECT transaction_isolation_level FROM sys.dm_exec_sessions WHERE session_id = @@SPID",
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    assert_eq!(level, 5);
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resets_the_isolation_level_after_a_transaction() -> anyhow::Result<()> {
+    async fn isolation_level(conn: &mut MssqlConnection) -> anyhow::Result<i16> {
+        let (level,): (i16,) = sqlx::query_as(
+            "SELECT transaction_isolation_level FROM sys.dm_exec_sessions WHERE session_id = @@SPID",
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(level)
+    }
+
+    let mut conn = new::<Mssql>().await?;
+
+    // 2 = READ COMMITTED
+    conn.begin_with_isolation_level(MssqlIsolationLevel::Serializable)
+        .await?
+        .commit()
+        .await?;
+
+    assert_eq!(isolation_level(&mut conn).await?, 2);
+
+    conn.begin_with_isolation_level(MssqlIsolationLevel::Serializable)
+        .await?
+        .rollback()
+        .await?;
+
+    assert_eq!(isolation_level(&mut conn).await?, 2);
+
+    // dropped without a commit or rollback
+    conn.begin_with_isolation_level(MssqlIsolationLevel::Serializable)
+        .await?;
+
+    assert_eq!(isolation_level(&mut conn).await?, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fails_to_commit_after_the_server_rolled_back() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute("CREATE TABLE #users (id INTEGER PRIMARY KEY); SET XACT_ABORT ON")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+    let mut tx2 = tx.begin().await?;
+
+    sqlx::query("INSERT INTO #users (id) VALUES (1)")
+        .execute(&mut tx2)
+        .await?;
+
+    // with XACT_ABORT set, the server rolls back the whole transaction on any error
+    let res = sqlx::query("INSERT INTO #users (id) VALUES (1)")
+        .execute(&mut tx2)
+        .await;
+
+    assert!(res.is_err());
+
+    let res = tx2.commit().await;
+
+    assert!(matches!(res, Err(sqlx::Error::TransactionRolledBack)));
+
+    tx.rollback().await?;
+
+    let (count,): (i32,) = sqlx::query_as("SELECT COUNT(*) FROM #users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_call_a_stored_procedure() -> anyhow::Result<()> {
+    let mut conn = new::<Mssql>().await?;
+
+    conn.execute(
+        r#"
+CREATE PROCEDURE #sqlx_add @a INT, @b INT, @sum INT OUTPUT, @message NVARCHAR(100) OUTPUT
+AS
+BEGIN
+    SELECT @a AS a, @b AS b;
+    SET @sum = @a + @b;
+    SET @message = CONCAT(@a, ' + ', @b, ' = ', @sum);
+    RETURN 7;
+END
+        "#,
+    )
+    .await?;
+
+    let result = conn
+        .call("#sqlx_add")
+        .bind(1_i32)
+        .bind_named("b", 2_i32)
+        .bind_output("@sum", 0_i32)
+        .bind_output("@message", "")
+        .execute()
+        .await?;
+
+    assert_eq!(result.result_sets().len(), 1);
+
+    let row = result.rows().next().unwrap();
+
+    assert_eq!(row.try_get::<i32, _>(0)?, 1);
+    assert_eq!(row.try_get::<i32, _>(1)?, 2);
+
+    assert_eq!(result.try_get_output::<i32>("sum")?, 3);
+    assert_eq!(result.try_get_output::<String>("@message")?, "1 + 2 = 3");
+    assert_eq!(result.return_status(), Some(7));
+
+    Ok(())
+}
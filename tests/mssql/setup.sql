@@ -4,6 +4,9 @@ IF DB_ID('sqlx') IS NULL
     END;
 GO
 
+ALTER DATABASE sqlx SET ALLOW_SNAPSHOT_ISOLATION ON;
+GO
+
 USE sqlx;
 GO
 
@@ -1,10 +1,18 @@
+extern crate time_ as time;
+
 use sqlx::mssql::Mssql;
-use sqlx_test::test_type;
+use sqlx_test::{test_decode_type, test_type};
 
 test_type!(null<Option<i32>>(Mssql,
     "CAST(NULL as INT)" == None::<i32>
 ));
 
+test_type!(bool(
+    Mssql,
+    "CAST(1 AS BIT)" == true,
+    "CAST(0 AS BIT)" == false
+));
+
 test_type!(i8(
     Mssql,
     "CAST(5 AS TINYINT)" == 5_i8,
@@ -35,3 +43,125 @@ test_type!(str<String>(Mssql,
     "'this is foo'" == "this is foo",
     "''" == "",
 ));
+
+test_type!(bytes<Vec<u8>>(Mssql,
+    "0xDEADBEEF" == vec![0xDE_u8, 0xAD, 0xBE, 0xEF],
+    "CAST(0x0001 AS BINARY(2))" == vec![0_u8, 1],
+));
+
+#[cfg(feature = "uuid")]
+test_type!(uuid<sqlx::types::Uuid>(Mssql,
+    "CAST('b731678f-636f-4135-bc6f-19440c13bd19' AS UNIQUEIDENTIFIER)"
+        == sqlx::types::Uuid::parse_str("b731678f-636f-4135-bc6f-19440c13bd19").unwrap(),
+    "CAST('00000000-0000-0000-0000-000000000000' AS UNIQUEIDENTIFIER)"
+        == sqlx::types::Uuid::nil()
+));
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use super::*;
+    use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+    use sqlx_test::test_decode_type;
+
+    test_type!(chrono_date<NaiveDate>(
+        Mssql,
+        "CAST('2001-01-05' AS DATE)" == NaiveDate::from_ymd(2001, 1, 5),
+        "CAST('0001-01-01' AS DATE)" == NaiveDate::from_ymd(1, 1, 1)
+    ));
+
+    test_type!(chrono_time<NaiveTime>(
+        Mssql,
+        "CAST('05:10:20.1151' AS TIME(4))" == NaiveTime::from_hms_micro(5, 10, 20, 115100),
+        "CAST('23:59:59.9999999' AS TIME)" == NaiveTime::from_hms_nano(23, 59, 59, 999_999_900)
+    ));
+
+    test_type!(chrono_date_time<NaiveDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20' AS DATETIME2)" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20),
+        "CAST('2019-01-02 05:10:20.115100' AS DATETIME2)"
+            == NaiveDate::from_ymd(2019, 1, 2).and_hms_micro(5, 10, 20, 115100)
+    ));
+
+    test_decode_type!(chrono_legacy_date_time<NaiveDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20' AS DATETIME)" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20),
+        "CAST('1753-01-01 00:00:00' AS DATETIME)" == NaiveDate::from_ymd(1753, 1, 1).and_hms(0, 0, 0),
+        "CAST('2019-01-02 05:10:00' AS SMALLDATETIME)" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 0)
+    ));
+
+    test_type!(chrono_date_time_offset<DateTime::<Utc>>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20.115100 +00:00' AS DATETIMEOFFSET)"
+            == DateTime::<Utc>::from_utc(
+                NaiveDate::from_ymd(2019, 1, 2).and_hms_micro(5, 10, 20, 115100),
+                Utc,
+            ),
+        "CAST('2019-01-02 07:10:20 +02:00' AS DATETIMEOFFSET)"
+            == DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20), Utc)
+    ));
+}
+
+#[cfg(feature = "time")]
+mod time_tests {
+    use super::*;
+    use sqlx::types::time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+    use sqlx_test::test_decode_type;
+    use time::{date, time};
+
+    test_type!(time_date<Date>(
+        Mssql,
+        "CAST('2001-01-05' AS DATE)" == date!(2001 - 1 - 5),
+        "CAST('0001-01-01' AS DATE)" == Date::try_from_ymd(1, 1, 1).unwrap()
+    ));
+
+    test_type!(time_time<Time>(
+        Mssql,
+        "CAST('05:10:20.1151' AS TIME(4))" == time!(5:10:20.115100),
+        "CAST('23:59:59.9999999' AS TIME)" == time!(23:59:59.9999999)
+    ));
+
+    test_type!(time_date_time<PrimitiveDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20' AS DATETIME2)" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
+        "CAST('2019-01-02 05:10:20.115100' AS DATETIME2)"
+            == date!(2019 - 1 - 2).with_time(time!(5:10:20.115100))
+    ));
+
+    test_decode_type!(time_legacy_date_time<PrimitiveDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20' AS DATETIME)" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
+        "CAST('2019-01-02 05:10:00' AS SMALLDATETIME)" == date!(2019 - 1 - 2).with_time(time!(5:10))
+    ));
+
+    test_type!(time_date_time_offset<OffsetDateTime>(
+        Mssql,
+        "CAST('2019-01-02 05:10:20.115100 +00:00' AS DATETIMEOFFSET)"
+            == date!(2019 - 1 - 2)
+                .with_time(time!(5:10:20.115100))
+                .assume_utc(),
+        "CAST('2019-01-02 07:10:20 +02:00' AS DATETIMEOFFSET)"
+            == date!(2019 - 1 - 2)
+                .with_time(time!(7:10:20))
+                .assume_offset(UtcOffset::hours(2))
+    ));
+}
+
+#[cfg(feature = "bigdecimal")]
+test_type!(decimal<sqlx::types::BigDecimal>(Mssql,
+    "CAST(0 AS DECIMAL(1, 0))" == "0".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(1 AS DECIMAL(1, 0))" == "1".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(-1 AS DECIMAL(1, 0))" == "-1".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(10000 AS DECIMAL(5, 0))" == "10000".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(0.1 AS DECIMAL(2, 1))" == "0.1".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(12345.6789 AS DECIMAL(9, 4))" == "12345.6789".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(-12345678901234567890.123456789 AS DECIMAL(29, 9))"
+        == "-12345678901234567890.123456789".parse::<sqlx::types::BigDecimal>().unwrap(),
+));
+
+#[cfg(feature = "bigdecimal")]
+test_decode_type!(money<sqlx::types::BigDecimal>(Mssql,
+    "CAST(12345.6789 AS MONEY)" == "12345.6789".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(-922337203685477.5808 AS MONEY)"
+        == "-922337203685477.5808".parse::<sqlx::types::BigDecimal>().unwrap(),
+    "CAST(-1.5 AS SMALLMONEY)" == "-1.5".parse::<sqlx::types::BigDecimal>().unwrap(),
+));
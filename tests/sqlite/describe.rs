@@ -45,10 +45,142 @@ async fn it_describes_expression() -> anyhow::Result<()> {
     let columns = d.columns;
 
     assert_eq!(columns[0].name, "1 + 10");
-    assert_eq!(columns[0].not_null, None);
+    assert_eq!(columns[0].not_null, Some(true));
+
+    // inferred from the program of the statement
+    assert_eq!(type_names(&columns), ["BIGINT"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_expressions_of_columns() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe("SELECT text || '!', owner_id + 1, id * 2.5, lower(text), NULL FROM tweet")
+        .await?;
+
+    let columns = d.columns;
+
+    assert_eq!(columns[0].not_null, Some(true));
+    assert_eq!(columns[1].not_null, Some(false));
+    assert_eq!(columns[2].not_null, Some(true));
+    assert_eq!(columns[3].not_null, Some(true));
+    assert_eq!(columns[4].not_null, Some(false));
+
+    assert_eq!(type_names(&columns), ["TEXT", "BIGINT", "FLOAT", "TEXT"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_aggregates() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe("SELECT COUNT(*), MAX(id), SUM(owner_id), AVG(id), COUNT(owner_id) FROM tweet")
+        .await?;
+
+    let columns = d.columns;
+
+    assert_eq!(columns[0].not_null, Some(true));
+    assert_eq!(columns[1].not_null, Some(false));
+    assert_eq!(columns[2].not_null, Some(false));
+    assert_eq!(columns[3].not_null, Some(false));
+    assert_eq!(columns[4].not_null, Some(true));
+
+    assert_eq!(
+        type_names(&columns),
+        ["BIGINT", "BIGINT", "BIGINT", "FLOAT", "BIGINT"]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_grouped_and_ordered_expressions() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe(
+            "SELECT is_sent, COUNT(*) AS count, upper(MIN(text)) AS first FROM tweet \
+             GROUP BY is_sent ORDER BY count DESC",
+        )
+        .await?;
+
+    let columns = d.columns;
+
+    assert_eq!(columns[0].not_null, Some(true));
+    assert_eq!(columns[1].not_null, Some(true));
+    assert_eq!(columns[2].not_null, Some(false));
+
+    assert_eq!(type_names(&columns), ["BOOLEAN", "BIGINT", "TEXT"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_left_join() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe(
+            "SELECT a.name, t.text, t.id + 1 FROM accounts a \
+             LEFT JOIN tweet t ON t.owner_id = a.id",
+        )
+        .await?;
+
+    let columns = d.columns;
+
+    assert_eq!(columns[0].not_null, Some(true));
+
+    // NOT NULL in the table, but there may be no tweet to join
+    assert_eq!(columns[1].not_null, Some(false));
+    assert_eq!(columns[2].not_null, Some(false));
+
+    assert_eq!(type_names(&columns), ["TEXT", "TEXT", "BIGINT"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_compound_select() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe("SELECT id + 1 FROM tweet UNION SELECT NULL")
+        .await?;
+
+    let columns = d.columns;
+
+    assert_eq!(columns[0].not_null, Some(false));
+    assert_eq!(type_names(&columns), ["BIGINT"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_variadic_functions() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe(
+            "SELECT max(owner_id, 1), min(owner_id, 1), max(1, NULL), coalesce(owner_id, NULL), \
+             coalesce(owner_id, 1) FROM tweet",
+        )
+        .await?;
+
+    let columns = d.columns;
+
+    // the number of arguments of a call to a variadic function is not known, and with it
+    // whether any of them can be NULL
+    assert_ne!(columns[0].not_null, Some(true));
+    assert_ne!(columns[1].not_null, Some(true));
+    assert_ne!(columns[2].not_null, Some(true));
 
-    // SQLite cannot infer types for expressions
-    assert_eq!(columns[0].type_info, None);
+    assert_eq!(columns[3].not_null, Some(false));
+    assert_eq!(columns[4].not_null, Some(true));
 
     Ok(())
 }
@@ -71,3 +71,50 @@ async fn macro_select_from_view() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn macro_select_with_runtime_collation() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+    use sqlx::Connect;
+
+    let options = dotenv::var("DATABASE_URL")?
+        .parse::<SqliteConnectOptions>()
+        .map_err(|e| anyhow::anyhow!(e))?
+        .collation("nocase_rev", |a, b| b.to_lowercase().cmp(&a.to_lowercase()));
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    // the collation is only known at runtime, the macro must still be able to prepare this
+    let account = sqlx::query!("SELECT id, name FROM accounts ORDER BY name COLLATE nocase_rev")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(1, account.id);
+    assert_eq!("Herp Derpinson", account.name);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "bigdecimal", any(feature = "chrono", feature = "time")))]
+#[sqlx_macros::test]
+async fn macro_select_numeric_and_date() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let product = sqlx::query!("SELECT price, released FROM products")
+        .fetch_one(&mut conn)
+        .await?;
+
+    // a NUMERIC column is never taken for a date, nor a DATE column for a number
+    let price: sqlx::types::BigDecimal = product.price;
+    assert_eq!(price.to_string(), "9.99");
+
+    #[cfg(feature = "time")]
+    let released: sqlx::types::time::Date = product.released;
+
+    #[cfg(not(feature = "time"))]
+    let released: sqlx::types::chrono::NaiveDate = product.released;
+
+    assert_eq!(released.to_string(), "2020-07-01");
+
+    Ok(())
+}
@@ -6,3 +6,10 @@ CREATE TABLE tweet
     is_sent  BOOLEAN NOT NULL DEFAULT TRUE,
     owner_id BIGINT
 );
+
+CREATE TABLE products
+(
+    id       INTEGER PRIMARY KEY,
+    price    NUMERIC NOT NULL,
+    released DATE    NOT NULL
+);
@@ -1,8 +1,10 @@
 use futures::TryStreamExt;
-use sqlx::{
-    query, sqlite::Sqlite, Connect, Connection, Executor, Row, SqliteConnection, SqlitePool,
+use sqlx::sqlite::{
+    Sqlite, SqliteConnectOptions, SqliteFunction, SqliteJournalMode, SqliteSynchronous,
 };
+use sqlx::{query, Connect, Connection, Executor, Row, SqliteConnection, SqlitePool};
 use sqlx_test::new;
+use std::time::Duration;
 
 #[sqlx_macros::test]
 async fn it_connects() -> anyhow::Result<()> {
@@ -78,7 +80,7 @@ async fn it_fetches_and_inflates_row() -> anyhow::Result<()> {
 #[sqlx_macros::test]
 async fn it_fetches_in_loop() -> anyhow::Result<()> {
     // this is trying to check for any data races
-    // there were a few that triggered *sometimes* while building out StatementWorker
+    // there were a few that triggered *sometimes* while building out the statement worker
     for _ in 0..1000_usize {
         let mut conn = new::<Sqlite>().await?;
         let v: Vec<(i32,)> = sqlx::query_as("SELECT 1").fetch_all(&mut conn).await?;
@@ -123,6 +125,564 @@ async fn it_opens_temp_on_disk() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_applies_connect_options() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new()
+        .journal_mode(SqliteJournalMode::Memory)
+        .synchronous(SqliteSynchronous::Off)
+        .foreign_keys(true)
+        .busy_timeout(Duration::from_millis(250));
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(journal_mode, "memory");
+
+    let synchronous: i32 = sqlx::query_scalar("PRAGMA synchronous")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(synchronous, 0);
+
+    let foreign_keys: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(foreign_keys);
+
+    let busy_timeout: i32 = sqlx::query_scalar("PRAGMA busy_timeout")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(busy_timeout, 250);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_opens_read_only() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite:tests/sqlite/sqlite.db?mode=ro").await?;
+
+    let res = conn.execute("CREATE TABLE read_only (id INTEGER)").await;
+
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_does_not_create_if_missing() -> anyhow::Result<()> {
+    let res = SqliteConnection::connect("sqlite:tests/sqlite/does-not-exist.db?mode=rw").await;
+
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_calls_scalar_functions() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new()
+        .function(
+            SqliteFunction::scalar("slugify", |title: String| {
+                title.to_lowercase().replace(' ', "-")
+            })
+            .deterministic(true),
+        )
+        .function(SqliteFunction::scalar(
+            "distance",
+            |x1: f64, y1: f64, x2: f64, y2: f64| ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt(),
+        ))
+        .function(SqliteFunction::scalar("always_null", || None::<i32>))
+        .function(SqliteFunction::scalar("explode", |_: i32| -> i32 {
+            panic!("boom")
+        }));
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let (slug, distance, nothing): (String, f64, Option<i32>) =
+        sqlx::query_as("SELECT slugify(?), distance(0, 0, 3, 4), always_null()")
+            .bind("Hello World")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(slug, "hello-world");
+    assert_eq!(distance, 5.0);
+    assert_eq!(nothing, None);
+
+    let res = conn.execute("SELECT explode(1)").await;
+
+    assert!(res.is_err());
+
+    conn.create_function(&SqliteFunction::scalar("double", |v: i64| v * 2))
+        .await?;
+
+    let doubled: i64 = sqlx::query_scalar("SELECT double(21)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(doubled, 42);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_calls_aggregate_and_window_functions() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new()
+        .function(SqliteFunction::aggregate(
+            "product",
+            1_i64,
+            |product: &mut i64, value: i64| *product *= value,
+            |product| product,
+        ))
+        .function(SqliteFunction::window(
+            "moving_sum",
+            0_i64,
+            |sum: &mut i64, value: i64| *sum += value,
+            |sum: &mut i64, value: i64| *sum -= value,
+            |sum: &i64| *sum,
+        ));
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let product: i64 = sqlx::query_scalar(
+        "SELECT product(value) FROM (SELECT 2 AS value UNION ALL SELECT 3 UNION ALL SELECT 7)",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(product, 42);
+
+    // with no rows the aggregate finalizes its initial state
+    let product: i64 = sqlx::query_scalar("SELECT product(value) FROM (SELECT 1 AS value) WHERE 0")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(product, 1);
+
+    let sums: Vec<i64> = sqlx::query_scalar(
+        "SELECT moving_sum(value) OVER (ORDER BY value ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) \
+         FROM (SELECT 1 AS value UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4)",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(sums, vec![1, 3, 5, 7]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_registers_functions_on_each_pooled_connection() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new().function(SqliteFunction::scalar("answer", || 42_i32));
+
+    let pool = SqlitePool::builder()
+        .min_size(2)
+        .max_size(2)
+        .test_on_acquire(false)
+        .build_with(options)
+        .await?;
+
+    let mut a = pool.acquire().await?;
+    let mut b = pool.acquire().await?;
+
+    for conn in &mut [&mut a, &mut b] {
+        let answer: i32 = sqlx::query_scalar("SELECT answer()")
+            .fetch_one(&mut **conn)
+            .await?;
+
+        assert_eq!(answer, 42);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_orders_by_custom_collations() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new()
+        .collation("reverse", |a, b| b.cmp(a))
+        .collation("by_length", |a, b| {
+            a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+        });
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    conn.execute("CREATE TABLE words (word TEXT NOT NULL COLLATE by_length)")
+        .await?;
+
+    conn.execute("INSERT INTO words (word) VALUES ('ccc'), ('a'), ('bb'), ('aa')")
+        .await?;
+
+    let words: Vec<String> = sqlx::query_scalar("SELECT word FROM words ORDER BY word")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(words, ["a", "aa", "bb", "ccc"]);
+
+    let words: Vec<String> =
+        sqlx::query_scalar("SELECT word FROM words ORDER BY word COLLATE reverse")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(words, ["ccc", "bb", "aa", "a"]);
+
+    // text that is not valid UTF-8 is still compared, as if it were U+FFFD
+    let words: Vec<Vec<u8>> = sqlx::query_scalar(
+        "SELECT CAST(word AS BLOB) FROM (SELECT CAST(x'ff' AS TEXT) AS word UNION ALL SELECT 'zz') \
+            ORDER BY word COLLATE reverse",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(words, [vec![0xff], b"zz".to_vec()]);
+
+    let res = conn.execute("SELECT 1 ORDER BY 1 COLLATE unknown").await;
+
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_backs_up_to_another_connection() -> anyhow::Result<()> {
+    let mut source = SqliteConnection::connect(":memory:").await?;
+
+    source
+        .execute(
+            r#"
+CREATE TABLE items (id INTEGER PRIMARY KEY, data BLOB NOT NULL);
+
+WITH RECURSIVE seq(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < 200)
+INSERT INTO items (data) SELECT zeroblob(1024) FROM seq;
+            "#,
+        )
+        .await?;
+
+    let mut destination = SqliteConnection::connect(":memory:").await?;
+    let mut steps = Vec::new();
+
+    source
+        .backup_to(&mut destination)
+        .pages_per_step(10)
+        .on_progress(|progress| steps.push(progress))
+        .await?;
+
+    assert!(steps.len() > 1);
+    assert_eq!(steps.last().map(|p| p.remaining()), Some(0));
+    assert!(steps
+        .iter()
+        .all(|p| p.page_count() == steps[0].page_count()));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut destination)
+        .await?;
+
+    assert_eq!(count, 200);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_backs_up_to_a_file() -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join(format!("sqlx-backup-{}.db", std::process::id()));
+
+    let mut source = SqliteConnection::connect(":memory:").await?;
+
+    source
+        .execute("CREATE TABLE items (name TEXT); INSERT INTO items VALUES ('a'), ('b');")
+        .await?;
+
+    source.backup_to(path.as_path()).await?;
+
+    let mut backup =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path)).await?;
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM items ORDER BY name")
+        .fetch_all(&mut backup)
+        .await?;
+
+    backup.close().await?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(names, ["a", "b"]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reads_and_writes_blobs_incrementally() -> anyhow::Result<()> {
+    use sqlx_rt::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+    use std::io::SeekFrom;
+
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE attachments (id INTEGER PRIMARY KEY, data BLOB NOT NULL);
+INSERT INTO attachments (id, data) VALUES (1, zeroblob(65536)), (2, x'00010203');
+        "#,
+    )
+    .await?;
+
+    let chunk: Vec<u8> = (0..=255).collect();
+
+    {
+        let mut blob = conn.open_blob("attachments", "data", 1, false).await?;
+
+        assert_eq!(blob.len(), 65536);
+
+        for _ in 0..256 {
+            blob.write_all(&chunk).await?;
+        }
+
+        // the BLOB cannot grow
+        assert_eq!(blob.write(&chunk).await?, 0);
+
+        blob.seek(SeekFrom::Start(1000)).await?;
+
+        let mut buf = [0_u8; 4];
+        blob.read_exact(&mut buf).await?;
+
+        assert_eq!(buf, [232, 233, 234, 235]);
+
+        blob.seek(SeekFrom::End(-2)).await?;
+
+        let mut rest = Vec::new();
+        blob.read_to_end(&mut rest).await?;
+
+        assert_eq!(rest, [254, 255]);
+
+        assert!(blob.seek(SeekFrom::Current(1)).await.is_err());
+
+        blob.reopen(2).await?;
+
+        let mut data = Vec::new();
+        blob.read_to_end(&mut data).await?;
+
+        assert_eq!(data, [0, 1, 2, 3]);
+    }
+
+    let data: Vec<u8> = sqlx::query_scalar("SELECT data FROM attachments WHERE id = 1")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(data.len(), 65536);
+    assert!(data.chunks(256).all(|c| c == &*chunk));
+
+    let mut blob = conn.open_blob("attachments", "data", 2, true).await?;
+
+    let err = blob.write_all(&[1]).await.unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+
+    assert!(conn
+        .open_blob("attachments", "data", 3, true)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_invokes_update_commit_and_rollback_hooks() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteOperation;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let commits = Arc::new(AtomicUsize::new(0));
+    let rollbacks = Arc::new(AtomicUsize::new(0));
+    let allow_commit = Arc::new(AtomicBool::new(true));
+
+    conn.set_update_hook({
+        let updates = Arc::clone(&updates);
+
+        move |update| {
+            updates.lock().unwrap().push((
+                update.operation(),
+                update.database().to_owned(),
+                update.table().to_owned(),
+                update.rowid(),
+            ))
+        }
+    });
+
+    conn.set_commit_hook({
+        let commits = Arc::clone(&commits);
+        let allow_commit = Arc::clone(&allow_commit);
+
+        move || {
+            commits.fetch_add(1, Ordering::SeqCst);
+            allow_commit.load(Ordering::SeqCst)
+        }
+    });
+
+    conn.set_rollback_hook({
+        let rollbacks = Arc::clone(&rollbacks);
+
+        move || {
+            rollbacks.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    conn.execute("INSERT INTO items (id, name) VALUES (1, 'a'), (2, 'b')")
+        .await?;
+    conn.execute("UPDATE items SET name = 'c' WHERE id = 2")
+        .await?;
+    conn.execute("DELETE FROM items WHERE id = 1").await?;
+
+    assert_eq!(
+        *updates.lock().unwrap(),
+        vec![
+            (
+                SqliteOperation::Insert,
+                "main".to_owned(),
+                "items".to_owned(),
+                1
+            ),
+            (
+                SqliteOperation::Insert,
+                "main".to_owned(),
+                "items".to_owned(),
+                2
+            ),
+            (
+                SqliteOperation::Update,
+                "main".to_owned(),
+                "items".to_owned(),
+                2
+            ),
+            (
+                SqliteOperation::Delete,
+                "main".to_owned(),
+                "items".to_owned(),
+                1
+            ),
+        ]
+    );
+
+    // each statement outside of a transaction commits on its own
+    assert_eq!(commits.load(Ordering::SeqCst), 3);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 0);
+
+    // a commit hook returning false turns the commit into a rollback
+    allow_commit.store(false, Ordering::SeqCst);
+
+    let res = conn
+        .execute("INSERT INTO items (id, name) VALUES (3, 'd')")
+        .await;
+
+    assert!(res.is_err());
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 1);
+
+    conn.clear_update_hook();
+    conn.clear_commit_hook();
+    conn.clear_rollback_hook();
+
+    conn.execute("INSERT INTO items (id, name) VALUES (4, 'e')")
+        .await?;
+
+    assert_eq!(updates.lock().unwrap().len(), 5);
+    assert_eq!(commits.load(Ordering::SeqCst), 4);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_extensions_that_fail_to_load() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new().extension("tests/sqlite/does-not-exist", None);
+
+    let err = SqliteConnection::connect_with(&options).await.unwrap_err();
+
+    assert!(err.as_database_error().is_some());
+    assert!(err.to_string().contains("does-not-exist"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_does_not_allow_loading_extensions_from_sql() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+
+    let res = conn
+        .execute("SELECT load_extension('tests/sqlite/does-not-exist')")
+        .await;
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "error returned from database: not authorized"
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shares_a_named_in_memory_database_across_a_pool() -> anyhow::Result<()> {
+    let pool = SqlitePool::builder()
+        .max_size(2)
+        .test_on_acquire(false)
+        .build("sqlite:file:sqlx-pool-shared?mode=memory&cache=shared")
+        .await?;
+
+    let mut a = pool.acquire().await?;
+    let mut b = pool.acquire().await?;
+
+    a.execute("CREATE TABLE items (name TEXT); INSERT INTO items VALUES ('a');")
+        .await?;
+
+    let name: String = sqlx::query_scalar("SELECT name FROM items")
+        .fetch_one(&mut b)
+        .await?;
+
+    assert_eq!(name, "a");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_keeps_a_shared_in_memory_database_alive() -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new().shared_memory("sqlx-keep-alive");
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    conn.execute("CREATE TABLE items (name TEXT); INSERT INTO items VALUES ('a');")
+        .await?;
+
+    conn.close().await?;
+
+    // every connection has been closed but the options hold on to the database
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    // without a shared cache, every in-memory connection has its own database
+    let private = SqliteConnectOptions::new()
+        .filename("sqlx-keep-alive")
+        .in_memory(true);
+    let mut conn = SqliteConnection::connect_with(&private).await?;
+
+    assert!(conn.execute("SELECT * FROM items").await.is_err());
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_fails_to_parse() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -172,6 +732,37 @@ fn it_binds_parameters() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_fails_to_decode_out_of_range_integers() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let res = sqlx::query_scalar::<_, u8>("SELECT 256")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(res.is_err());
+
+    let res = sqlx::query_scalar::<_, i16>("SELECT -32769")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(res.is_err());
+
+    let res = sqlx::query_scalar::<_, u64>("SELECT -1")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(res.is_err());
+
+    let v: u32 = sqlx::query_scalar("SELECT 4294967295")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(v, u32::MAX);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_executes_queries() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -269,3 +860,46 @@ SELECT id, text FROM _sqlx_test;
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_interrupts_a_statement_that_times_out() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let res = sqlx_rt::timeout(
+        Duration::from_millis(100),
+        conn.fetch_one(
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT COUNT(*) FROM c",
+        ),
+    )
+    .await;
+
+    assert!(res.is_err());
+
+    // the connection is usable again right away
+    let value: i32 = conn.fetch_one("SELECT 1").await?.try_get(0)?;
+
+    assert_eq!(1, value);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_interrupts_a_statement_when_its_stream_is_dropped() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    {
+        let mut cursor = conn.fetch(
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT COUNT(*) FROM c",
+        );
+
+        let res = sqlx_rt::timeout(Duration::from_millis(100), cursor.try_next()).await;
+
+        assert!(res.is_err());
+    }
+
+    let value: i32 = conn.fetch_one("SELECT 1").await?.try_get(0)?;
+
+    assert_eq!(1, value);
+
+    Ok(())
+}
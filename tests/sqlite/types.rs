@@ -1,5 +1,7 @@
+extern crate time_ as time;
+
 use sqlx::sqlite::Sqlite;
-use sqlx_test::test_type;
+use sqlx_test::{test_decode_type, test_type};
 
 test_type!(null<Option<i32>>(Sqlite,
     "NULL" == None::<i32>
@@ -7,10 +9,26 @@ test_type!(null<Option<i32>>(Sqlite,
 
 test_type!(bool(Sqlite, "FALSE" == false, "TRUE" == true));
 
+test_type!(i8(Sqlite, "127" == 127_i8, "-128" == -128_i8));
+
+test_type!(i16(Sqlite, "21415" == 21415_i16, "-21415" == -21415_i16));
+
 test_type!(i32(Sqlite, "94101" == 94101_i32));
 
 test_type!(i64(Sqlite, "9358295312" == 9358295312_i64));
 
+test_type!(u8(Sqlite, "255" == 255_u8, "0" == 0_u8));
+
+test_type!(u16(Sqlite, "65535" == 65535_u16));
+
+test_type!(u32(Sqlite, "4294967295" == 4294967295_u32));
+
+// NOTE: `u64` is decode-only, as SQLite cannot store values above `i64::MAX`
+test_decode_type!(u64(
+    Sqlite,
+    "9223372036854775807" == 9223372036854775807_u64
+));
+
 // NOTE: This behavior can be surprising. Floating-point parameters are widening to double which can
 //       result in strange rounding.
 test_type!(f32(Sqlite, "3.1410000324249268" == 3.141f32 as f64 as f32));
@@ -31,3 +49,172 @@ test_type!(bytes<Vec<u8>>(Sqlite,
     "X'0000000052'"
         == vec![0_u8, 0, 0, 0, 0x52]
 ));
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use super::*;
+    use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+    use sqlx_test::test_decode_type;
+
+    test_type!(chrono_date<NaiveDate>(
+        Sqlite,
+        "'2001-01-05'" == NaiveDate::from_ymd(2001, 1, 5),
+        "'2050-11-23'" == NaiveDate::from_ymd(2050, 11, 23)
+    ));
+
+    test_type!(chrono_time<NaiveTime>(
+        Sqlite,
+        "'05:10:20'" == NaiveTime::from_hms(5, 10, 20),
+        "'05:10:20.115100'" == NaiveTime::from_hms_micro(5, 10, 20, 115100)
+    ));
+
+    test_type!(chrono_date_time<NaiveDateTime>(
+        Sqlite,
+        "'2019-01-02 05:10:20'" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20),
+        "'2019-01-02 05:10:20.115'" == NaiveDate::from_ymd(2019, 1, 2).and_hms_milli(5, 10, 20, 115)
+    ));
+
+    test_type!(chrono_timestamp<DateTime::<Utc>>(
+        Sqlite,
+        "'2019-01-02 05:10:20.115100+00:00'"
+            == DateTime::<Utc>::from_utc(
+                NaiveDate::from_ymd(2019, 1, 2).and_hms_micro(5, 10, 20, 115100),
+                Utc,
+            )
+    ));
+
+    test_decode_type!(chrono_date_time_from_other<NaiveDateTime>(
+        Sqlite,
+        "'2019-01-02T05:10:20Z'" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20),
+        "'2019-01-02 05:10'" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 0),
+        "1546405820" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20),
+        "julianday('2019-01-02 05:10:20.115')"
+            == NaiveDate::from_ymd(2019, 1, 2).and_hms_milli(5, 10, 20, 115)
+    ));
+
+    test_decode_type!(chrono_timestamp_from_other<DateTime::<Utc>>(
+        Sqlite,
+        "'2019-01-02 07:10:20+02:00'"
+            == DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20), Utc),
+        "1546405820"
+            == DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20), Utc)
+    ));
+
+    test_decode_type!(chrono_date_from_other<NaiveDate>(
+        Sqlite,
+        "'2019-01-02 05:10:20'" == NaiveDate::from_ymd(2019, 1, 2),
+        "julianday('2019-01-02')" == NaiveDate::from_ymd(2019, 1, 2)
+    ));
+}
+
+#[cfg(feature = "time")]
+mod time_tests {
+    use super::*;
+    use sqlx::types::time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+    use sqlx_test::test_decode_type;
+    use time::{date, offset, time};
+
+    test_type!(time_date<Date>(
+        Sqlite,
+        "'2001-01-05'" == date!(2001 - 1 - 5),
+        "'2050-11-23'" == date!(2050 - 11 - 23)
+    ));
+
+    test_type!(time_time<Time>(
+        Sqlite,
+        "'05:10:20'" == time!(5:10:20),
+        "'05:10:20.115100'" == time!(5:10:20.115100)
+    ));
+
+    test_type!(time_date_time<PrimitiveDateTime>(
+        Sqlite,
+        "'2019-01-02 05:10:20'" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
+        "'2019-01-02 05:10:20.115'" == date!(2019 - 1 - 2).with_time(time!(5:10:20.115))
+    ));
+
+    test_type!(time_timestamp<OffsetDateTime>(
+        Sqlite,
+        "'2019-01-02 05:10:20.115100+00:00'"
+            == date!(2019 - 1 - 2)
+                .with_time(time!(5:10:20.115100))
+                .assume_utc(),
+        "'2019-01-02 07:10:20-03:30'"
+            == date!(2019 - 1 - 2)
+                .with_time(time!(7:10:20))
+                .assume_offset(offset!(-3:30))
+    ));
+
+    test_decode_type!(time_date_time_from_other<PrimitiveDateTime>(
+        Sqlite,
+        "'2019-01-02T05:10:20Z'" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
+        "'2019-01-02 05:10'" == date!(2019 - 1 - 2).with_time(time!(5:10)),
+        "1546405820" == date!(2019 - 1 - 2).with_time(time!(5:10:20)),
+        "julianday('2019-01-02 05:10:20.115')"
+            == date!(2019 - 1 - 2).with_time(time!(5:10:20.115))
+    ));
+
+    test_decode_type!(time_date_from_other<Date>(
+        Sqlite,
+        "'2019-01-02 05:10:20'" == date!(2019 - 1 - 2),
+        "julianday('2019-01-02')" == date!(2019 - 1 - 2)
+    ));
+}
+
+#[cfg(feature = "json")]
+mod json_tests {
+    use super::*;
+    use serde_json::{json, Value as JsonValue};
+    use sqlx::types::Json;
+    use sqlx_test::{test_decode_type, test_type};
+
+    test_type!(json<JsonValue>(
+        Sqlite,
+        "'\"Hello, World\"'" == json!("Hello, World"),
+        "'\"😎\"'" == json!("😎"),
+        "'\"🙋‍♀️\"'" == json!("🙋‍♀️"),
+        "'[\"Hello\",\"World!\"]'" == json!(["Hello", "World!"])
+    ));
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Friend {
+        name: String,
+        age: u32,
+    }
+
+    test_type!(json_struct<Json<Friend>>(
+        Sqlite,
+        "\'{\"name\":\"Joe\",\"age\":33}\'" == Json(Friend { name: "Joe".to_string(), age: 33 })
+    ));
+
+    test_decode_type!(json_from_json1<Json<Friend>>(
+        Sqlite,
+        "json_object('name', 'Joe', 'age', 33)" == Json(Friend { name: "Joe".to_string(), age: 33 }),
+        "json_set('{\"name\":\"Joe\",\"age\":21}', '$.age', 33)"
+            == Json(Friend { name: "Joe".to_string(), age: 33 })
+    ));
+}
+
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal_tests {
+    use super::*;
+    use sqlx::types::BigDecimal;
+    use sqlx_test::test_decode_type;
+
+    test_type!(decimal<BigDecimal>(
+        Sqlite,
+        "'0'" == "0".parse::<BigDecimal>().unwrap(),
+        "'1'" == "1".parse::<BigDecimal>().unwrap(),
+        "'-0.5'" == "-0.5".parse::<BigDecimal>().unwrap(),
+        "'12345.6789'" == "12345.6789".parse::<BigDecimal>().unwrap(),
+        "'123456789012345678901234567890.123456789'"
+            == "123456789012345678901234567890.123456789".parse::<BigDecimal>().unwrap()
+    ));
+
+    test_decode_type!(decimal_from_numeric<BigDecimal>(
+        Sqlite,
+        "CAST(10 AS NUMERIC)" == "10".parse::<BigDecimal>().unwrap(),
+        "CAST('12345.678' AS NUMERIC)" == "12345.678".parse::<BigDecimal>().unwrap(),
+        "9223372036854775807" == "9223372036854775807".parse::<BigDecimal>().unwrap(),
+        "0.1" == "0.1".parse::<BigDecimal>().unwrap()
+    ));
+}